@@ -4,51 +4,146 @@ use std::time::Instant;
 
 use sudidakt::{
     model::{CellIndex, ColumnIndex, Digit, Grid, RowIndex},
-    solver::{PossibleValues, Solver},
+    solver::{PossibleValues, Solver, Step},
 };
 
+use super::Settings;
+
 /// Runs the automated mode.
-pub fn run(iterator: impl Iterator<Item = (usize, Grid)>) {
-    for (index, grid) in iterator {
+pub fn run(iterator: impl Iterator<Item = (usize, Grid, Option<Grid>)>, settings: Settings) {
+    //  Number of grids verified against their solution, found unsound, and whose solved grid mismatched.
+    let (mut verified, mut unsound, mut mismatched) = (0, 0, 0);
+
+    for (index, grid, solution) in iterator {
         println!("Solving grid {index}");
 
         let start = Instant::now();
 
         let mut solver = Solver::new(grid);
 
+        solver.configure(settings.config.clone());
+
+        if settings.unique {
+            if let Err(e) = solver.assume_uniqueness() {
+                println!("Cannot assume uniqueness of grid {index}: {}", e);
+            }
+        }
+
         if let Err(e) = solver.solve() {
             println!("Failed to solve grid {index}: {}", e);
-            println!("");
+            println!();
 
             println!("Start grid:");
             display_grid(&grid);
-            println!("");
+            println!();
 
             println!("Current grid:");
             display_grid(&solver.grid());
-            println!("");
+            println!();
 
             println!("Possible values:");
             display_possible_values(&solver.possible_values());
-            println!("");
+            println!();
+
+            if let Some(solution) = solution {
+                verified += 1;
+
+                if !verify(index, &solver, &solution) {
+                    unsound += 1;
+                }
+            }
 
-            return;
+            break;
         }
 
         println!("Solved grid {index} in {:?}", start.elapsed());
+
+        if let Some(solution) = solution {
+            verified += 1;
+
+            if solver.grid() != solution {
+                mismatched += 1;
+
+                println!("Solved grid {index} differs from its solution:");
+                display_grid(&solution);
+                println!();
+            }
+
+            if !verify(index, &solver, &solution) {
+                unsound += 1;
+                continue;
+            }
+        }
+
+        if let Some(first) = solver.first_guess() {
+            let refinements = solver.refinements().get_events();
+            let guesses = refinements.iter().filter(|refinement| refinement.reason().is_guess()).count();
+
+            println!("Logic ran out on grid {index} after {first} refinements, {guesses} guess(es) were needed");
+        }
+
+        if settings.walkthrough {
+            let path = solver.minimal_path();
+
+            println!("Walkthrough of grid {index}, {} steps out of {}:", path.len(),
+                solver.placements().len() - grid.number_solved() + solver.refinements().len());
+
+            display_walkthrough(&path);
+            println!();
+        }
+    }
+
+    if verified != 0 {
+        println!("Verified {verified} grid(s) against their solution: {unsound} unsound, {mismatched} mismatched");
     }
 }
 
+//  Verifies the deductions against the solution, reporting the first unsound one.
+//
+//  Returns whether all deductions are sound.
+fn verify(index: usize, solver: &Solver, solution: &Grid) -> bool {
+    let Err(e) = solver.verify(solution) else { return true };
+
+    println!("Unsound deduction on grid {index}: {}", e);
+
+    if let Step::Refinement(_, refinement) = e.step() {
+        println!("    {:?}", refinement.reason());
+    }
+
+    println!();
+
+    false
+}
+
 //
 //  Display
 //
 
+fn display_walkthrough(path: &[Step]) {
+    for step in path {
+        match step {
+            Step::Placement(_, placement) => {
+                let cell = placement.cell();
+
+                println!("    r{}c{} = {}", cell.row().value() + 1, cell.column().value() + 1,
+                    placement.digit().value());
+            },
+            Step::Refinement(_, refinement) => {
+                let cell = refinement.cell();
+
+                println!("    r{}c{} <> {}\t{}", cell.row().value() + 1, cell.column().value() + 1,
+                    refinement.removed().value(), refinement.reason().name());
+            },
+        }
+    }
+}
+
 fn display_grid(grid: &Grid) {
     use std::fmt::Write;
 
     for row in RowIndex::all() {
         if row.value() == 3 || row.value() == 6 {
-            println!("");
+            println!();
         }
 
         let mut formatted = String::from("    ");
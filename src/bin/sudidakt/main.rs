@@ -2,7 +2,10 @@
 
 use std::{fs::File, io::BufReader, iter, ops::Range};
 
-use sudidakt::model::{CellIndex, Digit, Grid};
+use sudidakt::{
+    model::{CellIndex, Digit, Grid},
+    solver::{ALL_ANALYSES, Analysis, AnalyzerConfig},
+};
 
 mod automated;
 mod interactive;
@@ -10,13 +13,13 @@ mod interactive;
 fn main() {
     let args: Vec<_> = std::env::args().collect();
 
-    let (mode, problem) = parse_arguments(&args[..]);
+    let (mode, problem, settings) = parse_arguments(&args[..]);
 
     match mode {
         Mode::Interactive => interactive::run(problem.grid()),
         Mode::Automated => match problem {
-            Problem::Immediate(grid) => automated::run(iter::once((0, grid))),
-            Problem::Csv(filename, range, step) => automated::run(parse_csv(filename.as_str(), range, step)),
+            Problem::Immediate(grid, solution) => automated::run(iter::once((0, grid, solution)), settings),
+            Problem::Csv(filename, range, step) => automated::run(parse_csv(filename.as_str(), range, step), settings),
         },
     }
 }
@@ -33,32 +36,47 @@ enum Mode {
 
 #[derive(Clone, Debug)]
 enum Problem {
-    Immediate(Grid),
+    Immediate(Grid, Option<Grid>),
     Csv(String, Range<usize>, usize),
 }
 
 impl Problem {
     fn grid(&self) -> Grid {
         match self {
-            Problem::Immediate(grid) => grid.clone(),
+            Problem::Immediate(grid, _) => *grid,
             Problem::Csv(file, range, step) => parse_csv(file, range.clone(), *step).next().unwrap().1,
         }
     }
 }
 
+#[derive(Clone, Debug, Default)]
+struct Settings {
+    unique: bool,
+    walkthrough: bool,
+    config: AnalyzerConfig,
+}
+
 #[derive(Clone, Debug, Default)]
 struct Options {
     csv: bool,
     line: Option<usize>,
     range: Option<Range<usize>>,
     step: Option<usize>,
+    unique: bool,
+    walkthrough: bool,
+    config: AnalyzerConfig,
 }
 
-fn parse_arguments(args: &[String]) -> (Mode, Problem) {
+fn parse_arguments(args: &[String]) -> (Mode, Problem, Settings) {
     let (mode, args) = consume_mode(&args[1..]);
     let (options, args) = consume_options(args);
     let positional = consume_positional(args);
 
+    let settings = Settings {
+        unique: options.unique,
+        walkthrough: options.walkthrough,
+        config: options.config.clone(),
+    };
     let problem = compute_problem(options, positional);
 
     if let Problem::Csv(_, range, _) = &problem {
@@ -69,30 +87,47 @@ fn parse_arguments(args: &[String]) -> (Mode, Problem) {
         }
     }
 
-    (mode, problem)
+    (mode, problem, settings)
 }
 
 fn print_help() -> ! {
     eprintln!("sudidakt [[i]nteractive|[a]utomated] [options] <grid/file>");
-    eprintln!("");
+    eprintln!();
     eprintln!("Meta:");
     eprintln!("\t-h|--help\tPrint this help and exit.");
-    eprintln!("");
+    eprintln!();
     eprintln!("Modes:");
     eprintln!("\tinteractive\tSolve the problem manually, with sudidakt checking and helping.");
     eprintln!("\tautomated\tLet sudidakt solve the problem(s).");
-    eprintln!("");
+    eprintln!();
     eprintln!("Problems:");
     eprintln!("\t-c/--csv\tTreat argument as filename, instead of grid.");
     eprintln!("\t-l/--line LINE\tUse the specified problem in the CSV.");
     eprintln!("\t-r/--rangeSTART END\tUse the specified range of problems in the CSV.");
     eprintln!("\t-s/--step STEP\tOnly process every STEP line in the CSV.");
-    eprintln!("");
+    eprintln!();
+    eprintln!("Solver:");
+    eprintln!("\t-u/--unique\tAssume a unique solution, enabling uniqueness-based analyses.");
+    eprintln!("\t-a/--analyses LIST\tOnly enable the comma-separated analyses, in order, e.g. GroupInclusion,Fish.");
+    eprintln!("\t--max-subset SIZE\tLimit the size of naked subsets.");
+    eprintln!("\t--max-fish SIZE\tLimit the size of fishes.");
+    eprintln!("\t--max-franken SIZE\tLimit the size of Franken and Mutant fishes.");
+    eprintln!("\t--max-franken-fins FINS\tLimit the number of fins of Franken and Mutant fishes.");
+    eprintln!("\t--max-chain LENGTH\tLimit the number of nodes of X-Cycles and Alternating Inference Chains.");
+    eprintln!("\t--nishio-depth DEPTH\tLimit the number of placements propagated per Nishio assumption.");
+    eprintln!("\t--nishio-effort EFFORT\tLimit the number of Nishio assumptions per analysis.");
+    eprintln!("\t--max-forcing BRANCHES\tLimit the number of branches of forcing chains.");
+    eprintln!("\t--max-guess DEPTH\tLimit the nesting of guesses.");
+    eprintln!();
+    eprintln!("Output:");
+    eprintln!("\t-w/--walkthrough\tPrint the minimal path to the solution, once solved.");
+    eprintln!();
     eprintln!("The expected problem format is 81 characters left-to-right, top-to-bottom,");
     eprintln!("with zeros or dots for unknown digits. Spaces are ignored.");
-    eprintln!("");
+    eprintln!();
     eprintln!("The expected CSV format is an optional header, then one problem and");
-    eprintln!("optionally its solution per row.");
+    eprintln!("optionally its solution per row, separated by a comma. Once solved, the");
+    eprintln!("grid and every deduction are verified against the solution, if any.");
 
     std::process::exit(1);
 }
@@ -132,6 +167,26 @@ fn consume_options(mut args: &[String]) -> (Options, &[String]) {
         }
     }
 
+    fn parse_analyses(arg: Option<&String>, name: &str) -> Vec<Analysis> {
+        let Some(arg) = arg else {
+            eprintln!("{} expects a comma-separated list of analyses as argument", name);
+
+            std::process::exit(1);
+        };
+
+        arg.split(',')
+            .map(|analysis| {
+                let found = ALL_ANALYSES.into_iter().find(|candidate| format!("{:?}", candidate) == analysis.trim());
+
+                found.unwrap_or_else(|| {
+                    eprintln!("{} expects analyses among {:?}, not {}", name, ALL_ANALYSES, analysis);
+
+                    std::process::exit(1);
+                })
+            })
+            .collect()
+    }
+
     let mut options = Options::default();
 
     while let Some(arg) = args.first() {
@@ -166,6 +221,66 @@ fn consume_options(mut args: &[String]) -> (Options, &[String]) {
 
                 &args[2..]
             }
+            "-u" | "--unique" => {
+                options.unique = true;
+
+                &args[1..]
+            },
+            "-w" | "--walkthrough" => {
+                options.walkthrough = true;
+
+                &args[1..]
+            },
+            "-a" | "--analyses" => {
+                options.config.set_analyses(&parse_analyses(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-subset" => {
+                options.config.set_max_subset_size(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-fish" => {
+                options.config.set_max_fish_size(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-franken" => {
+                options.config.set_max_franken_fish_size(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-franken-fins" => {
+                options.config.set_max_franken_fish_fins(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-chain" => {
+                options.config.set_max_chain_length(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--nishio-depth" => {
+                options.config.set_nishio_depth(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--nishio-effort" => {
+                options.config.set_nishio_effort(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-forcing" => {
+                options.config.set_max_forcing_branches(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
+            "--max-guess" => {
+                options.config.set_max_guess_depth(parse_index(args.get(1), arg));
+
+                &args[2..]
+            },
             _ => print_help(),
         };
     }
@@ -203,7 +318,7 @@ fn compute_problem(options: Options, positional: &str) -> Problem {
             std::process::exit(1);
         }
 
-        return Problem::Immediate(parse_grid(positional));
+        return Problem::Immediate(parse_grid(positional), parse_solution(positional));
     }
 
     let filename = String::from(positional);
@@ -253,11 +368,22 @@ fn parse_grid(line: &str) -> Grid {
     grid
 }
 
+//  Parses the solution following the grid, after a `,`, if any.
+fn parse_solution(line: &str) -> Option<Grid> {
+    let (_, solution) = line.split_once(',')?;
+
+    if solution.trim().is_empty() {
+        return None;
+    }
+
+    Some(parse_grid(solution))
+}
+
 //
 //  Csv parsing.
 //
 
-fn parse_csv(filename: &str, range: Range<usize>, step: usize) -> impl Iterator<Item = (usize, Grid)> {
+fn parse_csv(filename: &str, range: Range<usize>, step: usize) -> impl Iterator<Item = (usize, Grid, Option<Grid>)> {
     use std::io::BufRead;
 
     let file = File::open(filename).expect("Csv file exists");
@@ -269,7 +395,7 @@ fn parse_csv(filename: &str, range: Range<usize>, step: usize) -> impl Iterator<
         .skip_while(|line| {
             let first = line.as_bytes()[0];
 
-            !first.is_ascii_digit() || first != b'.'
+            !first.is_ascii_digit() && first != b'.'
         })
         .enumerate()
         //  Skip not within range.
@@ -277,5 +403,5 @@ fn parse_csv(filename: &str, range: Range<usize>, step: usize) -> impl Iterator<
         //  Only take within range.
         .take(range.end - range.start)
         .step_by(step)
-        .map(|(index, line)| (index, parse_grid(&line)))
+        .map(|(index, line)| (index, parse_grid(&line), parse_solution(&line)))
 }
@@ -4,3 +4,6 @@
 
 pub mod model;
 pub mod solver;
+
+#[cfg(test)]
+mod test_utils;
@@ -1,5 +1,7 @@
 //! The various models exposed by the solver.
 
+pub mod candidate;
+pub mod candidate_set;
 pub mod cell_set;
 pub mod digit;
 pub mod digit_counter;
@@ -10,6 +12,8 @@ pub mod group;
 pub mod group_set;
 pub mod index;
 
+pub use candidate::Candidate;
+pub use candidate_set::CandidateSet;
 pub use cell_set::CellSet;
 pub use digit::Digit;
 pub use digit_counter::DigitCounter;
@@ -0,0 +1,29 @@
+//! A candidate: a digit which may be placed in a cell.
+
+use std::fmt;
+
+use super::{CellIndex, Digit};
+
+/// A `Candidate` is the possibility of a given digit in a given cell.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Candidate {
+    cell: CellIndex,
+    digit: Digit,
+}
+
+impl Candidate {
+    /// Creates an instance.
+    pub fn new(cell: CellIndex, digit: Digit) -> Self { Self { cell, digit, } }
+
+    /// Returns the cell of the candidate.
+    pub fn cell(&self) -> CellIndex { self.cell }
+
+    /// Returns the digit of the candidate.
+    pub fn digit(&self) -> Digit { self.digit }
+}
+
+impl fmt::Debug for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{:?}@{:?}", self.digit, self.cell)
+    }
+}
@@ -0,0 +1,169 @@
+//! A set of candidates.
+
+use std::{convert, fmt, iter};
+
+use super::{DIMENSION, Candidate, CellSet, Digit};
+
+/// Set of Candidate.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CandidateSet([CellSet; DIMENSION]);
+
+impl CandidateSet {
+    /// Creates an empty CandidateSet.
+    pub fn empty() -> CandidateSet { CandidateSet::default() }
+
+    /// Checks whether the set is empty.
+    pub fn is_empty(&self) -> bool { self.0.iter().all(CellSet::is_empty) }
+
+    /// Returns the number of elements in the set.
+    pub fn size(&self) -> usize { self.0.iter().map(CellSet::size).sum() }
+
+    /// Checks whether the set contains the indicated Candidate.
+    pub fn has(&self, candidate: Candidate) -> bool { self.0[Self::index(candidate.digit())].has(candidate.cell()) }
+
+    /// Adds the specified Candidate.
+    pub fn add(&mut self, candidate: Candidate) { self.0[Self::index(candidate.digit())].add(candidate.cell()) }
+
+    /// Removes the specified Candidate.
+    pub fn remove(&mut self, candidate: Candidate) { self.0[Self::index(candidate.digit())].remove(candidate.cell()) }
+
+    /// Returns the set of cells of the candidates with the specified digit.
+    pub fn of_digit(&self, digit: Digit) -> CellSet { self.0[Self::index(digit)] }
+
+    /// Returns the set of Candidates in either set.
+    pub fn union(&self, other: &CandidateSet) -> CandidateSet {
+        CandidateSet(std::array::from_fn(|index| self.0[index].union(&other.0[index])))
+    }
+
+    /// Returns the set of Candidates in both sets.
+    pub fn intersection(&self, other: &CandidateSet) -> CandidateSet {
+        CandidateSet(std::array::from_fn(|index| self.0[index].intersection(&other.0[index])))
+    }
+
+    /// Returns the set of Candidates in this set, but not in the argument.
+    pub fn difference(&self, other: &CandidateSet) -> CandidateSet {
+        CandidateSet(std::array::from_fn(|index| self.0[index].difference(&other.0[index])))
+    }
+
+    //  Internal: computes the index of a digit within the set.
+    fn index(digit: Digit) -> usize { digit.value() - 1 }
+}
+
+impl convert::From<Candidate> for CandidateSet {
+    fn from(candidate: Candidate) -> CandidateSet {
+        let mut result = CandidateSet::default();
+        result.add(candidate);
+        result
+    }
+}
+
+impl iter::FromIterator<Candidate> for CandidateSet {
+    fn from_iter<I>(iter: I) -> CandidateSet
+    where
+        I: IntoIterator<Item = Candidate>,
+    {
+        let mut result = CandidateSet::default();
+
+        for candidate in iter {
+            result.add(candidate);
+        }
+
+        result
+    }
+}
+
+impl fmt::Debug for CandidateSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_set().entries(*self).finish()
+    }
+}
+
+impl iter::IntoIterator for CandidateSet {
+    type Item = Candidate;
+    type IntoIter = CandidateSetIterator;
+
+    fn into_iter(self) -> Self::IntoIter { CandidateSetIterator(self.0, 0, self.0[0].into_iter()) }
+}
+
+/// Iterator over a set of Candidates, by digit then cell.
+#[derive(Clone)]
+pub struct CandidateSetIterator([CellSet; DIMENSION], usize, <CellSet as IntoIterator>::IntoIter);
+
+impl iter::Iterator for CandidateSetIterator {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cell) = self.2.next() {
+                let digit = Digit::new(self.1 + 1).expect("Valid digit");
+                return Some(Candidate::new(cell, digit));
+            }
+
+            if self.1 + 1 >= DIMENSION {
+                return None;
+            }
+
+            self.1 += 1;
+            self.2 = self.0[self.1].into_iter();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+use crate::model::CellIndex;
+
+#[test]
+fn empty_set() {
+    let empty = CandidateSet::default();
+
+    assert!(empty.is_empty());
+    assert_eq!(0, empty.size());
+    assert_eq!("{}", &format!("{:?}", empty));
+}
+
+#[test]
+fn crud_candidate_set() {
+    let three = candidate(3, 7);
+    let four = candidate(4, 2);
+
+    let mut set = CandidateSet::default();
+    set.add(three);
+
+    assert!(set.has(three));
+    assert!(!set.has(four));
+    assert_eq!("{7@3}", &format!("{:?}", set));
+
+    set.add(four);
+
+    assert!(set.has(three));
+    assert!(set.has(four));
+    assert_eq!(2, set.size());
+    assert_eq!("{2@4, 7@3}", &format!("{:?}", set));
+
+    set.remove(three);
+
+    assert!(!set.has(three));
+    assert!(set.has(four));
+    assert_eq!("{2@4}", &format!("{:?}", set));
+}
+
+#[test]
+fn operations_candidate_set() {
+    let left: CandidateSet = [candidate(1, 1), candidate(2, 1), candidate(2, 9)].into_iter().collect();
+    let right: CandidateSet = [candidate(2, 9), candidate(3, 9)].into_iter().collect();
+
+    assert_eq!("{1@1, 1@2, 9@2, 9@3}", &format!("{:?}", left.union(&right)));
+    assert_eq!("{9@2}", &format!("{:?}", left.intersection(&right)));
+    assert_eq!("{1@1, 1@2}", &format!("{:?}", left.difference(&right)));
+    assert_eq!("{2}", &format!("{:?}", left.of_digit(Digit::new(9).unwrap())));
+}
+
+fn candidate(cell: usize, digit: usize) -> Candidate {
+    Candidate::new(CellIndex::new(cell).expect("Valid CellIndex"), Digit::new(digit).expect("Valid Digit"))
+}
+
+}
@@ -2,7 +2,7 @@
 
 use std::{convert, fmt, iter};
 
-use super::{DIMENSION, CellIndex};
+use super::{DIMENSION, CellIndex, Group};
 
 /// Set of CellIndex.
 #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -15,6 +15,13 @@ impl CellSet {
     /// Creates a full CellSet, with all values set.
     pub fn full() -> CellSet { CellSet((1 << NUMBER_CELLS) - 1) }
 
+    /// Creates the CellSet of all cells sharing at least one group with the specified cell, excluding it.
+    pub fn peers(cell: CellIndex) -> CellSet {
+        let mut result: CellSet = Group::groups(cell).into_iter().flat_map(|group| group.cells()).collect();
+        result.remove(cell);
+        result
+    }
+
     /// Checks whether the set is empty.
     pub fn is_empty(&self) -> bool { self.0 == 0 }
 
@@ -30,6 +37,18 @@ impl CellSet {
     /// Removes the specified CellIndex.
     pub fn remove(&mut self, cell: CellIndex) { self.0 &= !Self::mask(cell) }
 
+    /// Returns whether the set is a subset of the argument.
+    pub fn is_subset_of(&self, other: &CellSet) -> bool { self.0 | other.0 == other.0 }
+
+    /// Returns the set of CellIndex in either set.
+    pub fn union(&self, other: &CellSet) -> CellSet { CellSet(self.0 | other.0) }
+
+    /// Returns the set of CellIndex in both sets.
+    pub fn intersection(&self, other: &CellSet) -> CellSet { CellSet(self.0 & other.0) }
+
+    /// Returns the set of CellIndex in this set, but not in the argument.
+    pub fn difference(&self, other: &CellSet) -> CellSet { CellSet(self.0 & !other.0) }
+
     //  Internal: computes the index of a cell within the set.
     fn index(cell: CellIndex) -> usize { cell.value() }
 
@@ -45,9 +64,28 @@ impl convert::From<CellIndex> for CellSet {
     }
 }
 
+impl convert::From<Group> for CellSet {
+    fn from(group: Group) -> CellSet { group.cells().into_iter().collect() }
+}
+
+impl iter::FromIterator<CellIndex> for CellSet {
+    fn from_iter<I>(iter: I) -> CellSet
+    where
+        I: IntoIterator<Item = CellIndex>,
+    {
+        let mut result = CellSet::default();
+
+        for cell in iter {
+            result.add(cell);
+        }
+
+        result
+    }
+}
+
 impl fmt::Debug for CellSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        f.debug_set().entries(self.into_iter()).finish()
+        f.debug_set().entries(*self).finish()
     }
 }
 
@@ -144,6 +182,32 @@ fn crud_cell_set() {
     assert_eq!("{}", &format!("{:?}", set));
 }
 
+#[test]
+fn operations_cell_set() {
+    let left: CellSet = [cell(1), cell(2), cell(3)].into_iter().collect();
+    let right: CellSet = [cell(3), cell(4)].into_iter().collect();
+
+    assert_eq!("{1, 2, 3, 4}", &format!("{:?}", left.union(&right)));
+    assert_eq!("{3}", &format!("{:?}", left.intersection(&right)));
+    assert_eq!("{1, 2}", &format!("{:?}", left.difference(&right)));
+    assert_eq!("{4}", &format!("{:?}", right.difference(&left)));
+
+    assert!(left.intersection(&right).is_subset_of(&left));
+    assert!(!left.is_subset_of(&right));
+}
+
+#[test]
+fn peers_cell_set() {
+    let peers = CellSet::peers(cell(0));
+
+    assert_eq!(20, peers.size());
+    assert!(!peers.has(cell(0)));
+    assert!(peers.has(cell(8)));
+    assert!(peers.has(cell(72)));
+    assert!(peers.has(cell(20)));
+    assert!(!peers.has(cell(30)));
+}
+
 fn cell(cell: usize) -> CellIndex { CellIndex::new(cell).expect("Valid CellIndex") }
 
 }
@@ -22,7 +22,7 @@ impl Digit {
         if let Some(non_zero) = NonZeroU8::new(value as u8) {
             Ok(Digit(non_zero))
         } else {
-            Err(format!("0 is never an appropriate digit"))
+            Err("0 is never an appropriate digit".to_string())
         }
     }
 
@@ -62,7 +62,7 @@ impl DigitCounter {
 
 impl fmt::Debug for DigitCounter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        f.debug_map().entries(self.into_iter()).finish()
+        f.debug_map().entries(*self).finish()
     }
 }
 
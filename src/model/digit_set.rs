@@ -27,6 +27,15 @@ impl DigitSet {
     /// Returns whether the set is a superset of the argument.
     pub fn is_superset_of(&self, other: &DigitSet) -> bool { other.is_subset_of(self) }
 
+    /// Returns the set of Digits in either set.
+    pub fn union(&self, other: &DigitSet) -> DigitSet { DigitSet(self.0 | other.0) }
+
+    /// Returns the set of Digits in both sets.
+    pub fn intersection(&self, other: &DigitSet) -> DigitSet { DigitSet(self.0 & other.0) }
+
+    /// Returns the set of Digits in this set, but not in the argument.
+    pub fn difference(&self, other: &DigitSet) -> DigitSet { DigitSet(self.0 & !other.0) }
+
     /// Adds the specified Digit.
     pub fn add(&mut self, digit: Digit) { self.0 |= Self::mask(digit) }
 
@@ -48,9 +57,24 @@ impl convert::From<Digit> for DigitSet {
     }
 }
 
+impl iter::FromIterator<Digit> for DigitSet {
+    fn from_iter<I>(iter: I) -> DigitSet
+    where
+        I: IntoIterator<Item = Digit>,
+    {
+        let mut result = DigitSet::default();
+
+        for digit in iter {
+            result.add(digit);
+        }
+
+        result
+    }
+}
+
 impl fmt::Debug for DigitSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        f.debug_set().entries(self.into_iter()).finish()
+        f.debug_set().entries(*self).finish()
     }
 }
 
@@ -141,6 +165,17 @@ fn crud_digit_set() {
     assert_eq!("{}", &format!("{:?}", set));
 }
 
+#[test]
+fn operations_digit_set() {
+    let left: DigitSet = [digit(1), digit(2), digit(3)].into_iter().collect();
+    let right: DigitSet = [digit(3), digit(4)].into_iter().collect();
+
+    assert_eq!("{1, 2, 3, 4}", &format!("{:?}", left.union(&right)));
+    assert_eq!("{3}", &format!("{:?}", left.intersection(&right)));
+    assert_eq!("{1, 2}", &format!("{:?}", left.difference(&right)));
+    assert_eq!("{4}", &format!("{:?}", right.difference(&left)));
+}
+
 fn digit(digit: usize) -> Digit { Digit::new(digit).expect("Valid Digit") }
 
 }
@@ -45,9 +45,24 @@ impl convert::From<Group> for GroupSet {
     }
 }
 
+impl iter::FromIterator<Group> for GroupSet {
+    fn from_iter<I>(iter: I) -> GroupSet
+    where
+        I: IntoIterator<Item = Group>,
+    {
+        let mut result = GroupSet::default();
+
+        for group in iter {
+            result.add(group);
+        }
+
+        result
+    }
+}
+
 impl fmt::Debug for GroupSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        f.debug_set().entries(self.into_iter()).finish()
+        f.debug_set().entries(*self).finish()
     }
 }
 
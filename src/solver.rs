@@ -1,21 +1,46 @@
 //! The Solver and its various hanger-ons.
 
+mod almost_locked_set;
 mod analysis;
 mod analyzer;
+mod analyzer_config;
+mod brute_force;
+mod chain_node;
+mod forcing_chain;
+mod guess;
+mod hint;
 mod journal;
+mod mistake;
 mod placement;
 mod placer;
 mod possible_values;
+mod provenance;
 mod refinement;
+#[allow(clippy::module_inception)]
 mod solver;
 
+pub use almost_locked_set::AlmostLockedSet;
 pub use analysis::{ALL_ANALYSES, NUMBER_ANALYSIS, Analysis};
+pub use analyzer_config::{
+    DEFAULT_MAX_CHAIN_LENGTH, DEFAULT_MAX_FISH_SIZE, DEFAULT_MAX_FORCING_BRANCHES, DEFAULT_MAX_FRANKEN_FISH_FINS,
+    DEFAULT_MAX_FRANKEN_FISH_SIZE, DEFAULT_MAX_GUESS_DEPTH, DEFAULT_MAX_SUBSET_SIZE, DEFAULT_NISHIO_DEPTH,
+    DEFAULT_NISHIO_EFFORT, AnalyzerConfig,
+};
+pub use brute_force::find_solutions;
+pub use chain_node::ChainNode;
+pub use forcing_chain::{ForcingBranch, ForcingContradiction, ForcingStep};
+pub use guess::Guess;
+pub use hint::Hint;
 pub use journal::{JournalCursor, JournalMultiCursor, JournalReader};
+pub use mistake::{Mistake, MistakeMode};
 pub use placement::Placement;
 pub use possible_values::PossibleValues;
-pub use refinement::{Refinement, RefinementReason};
-pub use solver::Solver;
+pub use provenance::{Premise, Step};
+pub use refinement::{ChainKind, ForcingChainKind, MedusaRule, Refinement, RefinementReason, UniqueRectangleKind};
+pub use solver::{
+    ConflictError, EliminationError, MoveError, ProgressStalled, Solver, UniquenessError, UnsoundDeduction,
+};
 
-use analyzer::Analyzer;
+use analyzer::{Analyzer, AnalyzerSnapshot};
 use journal::JournalWriter;
-use placer::Placer;
+use placer::{Placer, PlacerSnapshot};
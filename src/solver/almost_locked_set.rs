@@ -0,0 +1,24 @@
+//! An Almost Locked Set: N cells within a group, between which only N+1 digits are possible.
+
+use crate::model::{CellSet, DigitSet};
+
+/// An Almost Locked Set, or ALS.
+///
+/// An ALS is formed by N unsolved cells within a single group, between which only N+1 digits are possible. Should any
+/// of those digits be removed, the remaining N digits would be locked within the N cells.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AlmostLockedSet {
+    cells: CellSet,
+    digits: DigitSet,
+}
+
+impl AlmostLockedSet {
+    /// Creates an instance.
+    pub fn new(cells: CellSet, digits: DigitSet) -> Self { Self { cells, digits, } }
+
+    /// Returns the cells of the set.
+    pub fn cells(&self) -> CellSet { self.cells }
+
+    /// Returns the digits possible within the cells of the set.
+    pub fn digits(&self) -> DigitSet { self.digits }
+}
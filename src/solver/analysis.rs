@@ -7,9 +7,14 @@
 pub const NUMBER_ANALYSIS: usize = ALL_ANALYSES.len();
 
 /// All analyses, from cheapest to most expensive.
-pub const ALL_ANALYSES: [Analysis; 5] = [
+pub const ALL_ANALYSES: [Analysis; 24] = [
     Analysis::CellExclusion, Analysis::GroupExclusion, Analysis::GroupInclusion, Analysis::GroupOverlap,
-    Analysis::GroupSubsetInclusion,
+    Analysis::GroupSubsetInclusion, Analysis::Fish, Analysis::FinnedFish, Analysis::XCycle,
+    Analysis::BivalueUniversalGrave, Analysis::UniqueRectangle, Analysis::AvoidableRectangle, Analysis::SueDeCoq,
+    Analysis::AlmostLockedSetXZ, Analysis::AlmostLockedSetXYWing, Analysis::AlmostLockedSetChain,
+    Analysis::DeathBlossom, Analysis::Exocet, Analysis::Medusa, Analysis::AlternatingInferenceChain,
+    Analysis::FrankenFish, Analysis::Nishio, Analysis::PatternOverlay, Analysis::ForcingChain,
+    Analysis::BowmansBingo,
 ];
 
 /// The various analyses algorithms, from cheap to expensive.
@@ -42,10 +47,220 @@ pub enum Analysis {
     /// When a set of N digits is the only set of possible values for a set of N cells within a given group, then those
     /// N digits can only appear within those N cells.
     ///
-    /// This is an extension of the GroupInclusion analysis, to a degree, although it is much more costly to run.
+    /// This is an extension of the GroupInclusion analysis, to a degree, although it is much more costly to run. Every
+    /// subset size is searched, smallest first over all groups, hence hidden subsets are found as their complementary
+    /// naked subsets.
     ///
     /// #   Algorithmic Complexity
     ///
-    /// Cubic (time) in the number of cells in a group.
+    /// Linear (time) in the number of cells in a group, for each subset of the digits left within each group, that is
+    /// at most 2^9 = 512 subsets per group.
     GroupSubsetInclusion,
+    /// When, for a given digit, N rows (the base) only contain this digit within N columns (the cover), then each of
+    /// these columns must contain the digit within the base, hence the digit cannot appear elsewhere in the cover. The
+    /// same applies with rows and columns swapped.
+    ///
+    /// This covers X-Wing (N = 2), Swordfish (N = 3), and Jellyfish (N = 4).
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quartic (time) in the number of cells in a group, as combinations of up to 4 lines are enumerated.
+    Fish,
+    /// A Fish, for which a few extra candidates (the fins) of the base are outside the cover, all within a single
+    /// square. Either one of the fins holds the digit, or the Fish does, hence only the cells of the cover which are in
+    /// the square of the fins can be eliminated.
+    ///
+    /// This also covers Sashimi fishes, for which a base line has no candidate in the cover, save for the fins.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Octic (time) in the number of cells in a group, as combinations of up to 4 lines are enumerated for both base
+    /// and cover.
+    FinnedFish,
+    /// For a given digit, a chain alternates strong links, between the only 2 positions of the digit within a group,
+    /// and weak links, between positions seeing each other. Starting and ending with a strong link, either end holds
+    /// the digit, which thus cannot appear in any cell seeing both ends. Should the ends see each other, the chain is a
+    /// continuous loop, and the digit cannot appear in any cell seeing both ends of any weak link (X-Cycle).
+    ///
+    /// Nodes may be grouped: the positions of the digit within the overlap of a square and a line.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Cubic (time) in the number of positions of the digit, as a breadth-first search runs from each node.
+    XCycle,
+    /// Should all unsolved cells have 2 possible digits, save for a single cell with 3, then the digit of this cell
+    /// appearing thrice in each of its groups must be placed there, or the grid would have multiple solutions (BUG+1).
+    /// Applies only if, without this digit in this cell, every digit appears exactly twice, if at all, among the unsolved
+    /// cells of each group.
+    ///
+    /// Requires the grid to have a unique solution.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Linear (time) in the number of cells.
+    BivalueUniversalGrave,
+    /// Four unsolved cells at the corners of a rectangle spanning exactly 2 squares cannot all be reduced to the same
+    /// pair of digits, or the two digits could be swapped and the grid would have two solutions. Hence at least one of
+    /// the corners must hold another digit, which covers the types 1 to 6 and the hidden unique rectangles.
+    ///
+    /// Requires the grid to have a unique solution.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quartic (time) in the number of cells in a group, as all rectangles are enumerated.
+    UniqueRectangle,
+    /// Four cells at the corners of a rectangle spanning exactly 2 squares, none of them given, cannot be solved as two
+    /// pairs of the same digits along the diagonals, or the two digits could be swapped and the grid would have two
+    /// solutions.
+    ///
+    /// Requires the grid to have a unique solution.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quartic (time) in the number of cells in a group, as all rectangles are enumerated.
+    AvoidableRectangle,
+    /// A square and a line overlap in a set of 3 cells. If N of these cells have at least N+2 possible digits, and can
+    /// be combined with an Almost Locked Set in the rest of the line and another in the rest of the square, without any
+    /// digit in common, to form a locked set, then each digit is locked either in the line or in the square.
+    ///
+    /// This is an extension of the GroupOverlap analysis.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quadratic (time) in the number of subsets of the cells of a group outside the overlap.
+    SueDeCoq,
+    /// Two Almost Locked Sets, A and B, share a restricted common digit X, which can only appear in one of them, hence
+    /// either A or B is locked without X. Any other digit Z common to both must then appear in either, and cannot
+    /// appear in any cell seeing all cells of A and B which may hold Z.
+    ///
+    /// When A and B share 2 restricted common digits, both are locked, and many more eliminations are possible.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quadratic (time) in the number of Almost Locked Sets, which are limited to 5 cells.
+    AlmostLockedSetXZ,
+    /// Two Almost Locked Sets, A and B, each share a different restricted common digit, X and Y, with a third Almost
+    /// Locked Set, C. Should neither A nor B hold a digit Z common to both, they would be locked, and C would lose both
+    /// X and Y, hence Z cannot appear in any cell seeing all cells of A and B which may hold Z.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Cubic (time) in the number of Almost Locked Sets, which are limited to 5 cells.
+    AlmostLockedSetXYWing,
+    /// A chain of Almost Locked Sets, each sharing a restricted common digit with the next, consecutive restricted
+    /// commons being different. Should the first ALS not hold a digit Z, it would be locked, and so on until the last,
+    /// which would hold Z. Hence Z cannot appear in any cell seeing all cells of the first and last ALSs which may hold Z.
+    ///
+    /// This generalizes ALS-XZ (2 ALSs) and ALS-XY-Wing (3 ALSs) to chains of up to 5 ALSs.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Exponential (time) in the length of the chain, with the number of restricted commons of each ALS as base.
+    AlmostLockedSetChain,
+    /// A stem cell, each possible digit of which is attached to a petal: an Almost Locked Set all the cells of which
+    /// holding the digit see the stem. Whichever digit the stem holds, the petal attached to it is locked, hence a digit
+    /// Z common to all petals, and not possible in the stem, cannot appear in any cell seeing all cells of the petals
+    /// which may hold Z.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Exponential (time) in the number of petals, up to 3, with the number of Almost Locked Sets as base.
+    DeathBlossom,
+    /// Two base cells within a square and a line, holding 3 or 4 digits between them, and two target cells in the other
+    /// squares of the band, in the other lines. Should each base digit be confined to 2 lines within the cross-lines
+    /// (the columns of the targets, and the free column of the square of the base) outside the band, the 2 digits of
+    /// the base are the 2 digits of the targets (Junior Exocet).
+    ///
+    /// Partial: only lines parallel to the base are considered as cover houses, and only the digits of the targets and
+    /// of the base are restricted; the other eliminations of the standard pattern are not made.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Constant (time), as the number of possible patterns is fixed.
+    Exocet,
+    /// The candidates linked by strong links, either the 2 candidates of a bivalue cell or the 2 positions of a digit
+    /// within a group, form clusters which are colored with 2 alternating colors: all the candidates of one color are
+    /// true, or all the candidates of the other are. A color seen twice within a cell or a group, or emptying a cell,
+    /// is false, and an uncolored candidate seeing both colors is false (3D Medusa).
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quadratic (time) in the number of candidates, as each cluster is checked against every candidate.
+    Medusa,
+    /// A chain alternating strong and weak links between candidates of any digit, as an X-Cycle, with the addition of
+    /// the strong link between the 2 candidates of a bivalue cell, and the weak link between 2 candidates of a cell.
+    /// Either end of the chain is true, hence any candidate weakly linked to both is false (AIC).
+    ///
+    /// Nodes may be grouped: the candidates of a digit within the overlap of a square and a line.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Cubic (time) in the number of candidates, as a breadth-first search runs from each node.
+    AlternatingInferenceChain,
+    /// A Fish, possibly finned, whose base and cover mix rows, columns, and squares: Franken fishes mix lines of one
+    /// type with squares, and Mutant fishes mix all types of groups. The base groups must not overlap, and the cells
+    /// which can be eliminated must see all the fins, if any. The size and the number of fins are limited by the
+    /// configuration, to 3 by default.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Sextic (time) in the number of groups by default, as combinations of up to 3 groups are enumerated for both base
+    /// and cover.
+    FrankenFish,
+    /// A digit is assumed in a cell, and only the placements of this digit which follow are propagated, up to a given
+    /// depth. Should a group be left without any position for the digit, or should the rows left be unable to hold the
+    /// digit in distinct columns, the assumption is false (Nishio).
+    ///
+    /// This is a simpler, single digit, trial than forcing chains.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quadratic (time) in the dimension, for each placement propagated, for each assumption, up to a given effort.
+    Nishio,
+    /// For a given digit, a template is a placement of its 9 instances, one per row, column, and square, consistent
+    /// with the possible values. The solution being one of the templates, the digit cannot appear in a cell covered by
+    /// no template, and must appear in a cell covered by every template (Pattern Overlay Method).
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Linear (time) in the number of templates, which is at most 46,656 per digit.
+    PatternOverlay,
+    /// The last resort of logic: each candidate of a cell, or each position of a digit within a group, is assumed in
+    /// turn, and the singles following from the assumption are propagated. An assumption leading to a contradiction is
+    /// false, and a candidate removed by all the assumptions is false as well (forcing chains, and forcing nets).
+    ///
+    /// The trace of the propagation is kept as the explanation.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Quadratic (time) in the number of cells, for each cell and group, as singles are propagated for each branch.
+    ForcingChain,
+    /// Trial and error, once logic runs out: each candidate of the most constrained cell is assumed in turn, and the
+    /// singles following from the assumption are propagated. Should no contradiction be reached, the most constrained
+    /// cell left is guessed in turn, and so on, up to a given depth. An assumption all of whose guesses fail is false
+    /// (Bowman's Bingo).
+    ///
+    /// The failed guess is kept as the explanation, so that guesses can be told apart from logic.
+    ///
+    /// #   Algorithmic Complexity
+    ///
+    /// Exponential (time) in the depth, for each assumption; a handful of guesses suffice in practice.
+    BowmansBingo,
+}
+
+impl Analysis {
+    /// Returns whether the analysis assumes the grid has a unique solution.
+    ///
+    /// Such analyses are only enabled on request, see `Solver::assume_uniqueness`.
+    pub fn requires_uniqueness(&self) -> bool {
+        matches!(self, Analysis::BivalueUniversalGrave | Analysis::UniqueRectangle | Analysis::AvoidableRectangle)
+    }
+
+    /// Returns whether the analysis scans the whole grid, rather than the latest placements and refinements.
+    ///
+    /// Global analyses are only run once all preceding analyses have caught up.
+    pub fn is_global(&self) -> bool {
+        !matches!(self,
+            Analysis::CellExclusion | Analysis::GroupExclusion | Analysis::GroupInclusion | Analysis::GroupOverlap)
+    }
 }
@@ -1,25 +1,60 @@
 //! The Analyzer, which performs and keeps track of the various analyses.
 
-use crate::model::{DIMENSION, SQUARE_DIMENSION, CellIndex, CellSet, Group, GroupSet};
-use super::{ALL_ANALYSES, NUMBER_ANALYSIS, Analysis, JournalMultiCursor, JournalReader, JournalWriter, Placement, PossibleValues, Refinement, RefinementReason};
+mod almost_locked_set;
+mod bivalue_universal_grave;
+mod bowmans_bingo;
+mod candidate_graph;
+mod chain;
+mod exocet;
+mod fish;
+mod forcing_chain;
+mod medusa;
+mod nishio;
+mod pattern_overlay;
+mod sue_de_coq;
+mod unique_rectangle;
+
+use crate::model::{SQUARE_DIMENSION, Candidate, CellIndex, CellSet, ColumnIndex, Digit, DigitSet, Grid, Group, GroupIndex, GroupSet, RowIndex};
+use super::{DEFAULT_MAX_SUBSET_SIZE, NUMBER_ANALYSIS, Analysis, AnalyzerConfig, ForcingBranch, JournalMultiCursor, JournalReader, JournalWriter, Placement, PossibleValues, Refinement, RefinementReason};
+use super::provenance::PremiseTracker;
+
+use almost_locked_set::{AlmostLockedSetChain, AlmostLockedSetIndex, AlmostLockedSetXYWing, AlmostLockedSetXZ, DeathBlossom};
+use bivalue_universal_grave::BivalueUniversalGrave;
+use bowmans_bingo::BowmansBingo;
+use candidate_graph::CandidateGraph;
+use chain::{AlternatingInferenceChain, XCycle};
+use exocet::Exocet;
+use fish::{FinnedFish, Fish, FrankenFish};
+use forcing_chain::{ForcingChain, Propagation};
+use medusa::Medusa;
+use nishio::Nishio;
+use pattern_overlay::PatternOverlay;
+use sue_de_coq::SueDeCoq;
+use unique_rectangle::{AvoidableRectangle, UniqueRectangle};
 
 /// The Analyzer can incrementally perform the various analyses.
-#[derive(Clone, Debug)]
+///
+/// The Analyzer is not Clone, as its journal of refinements is shared with its readers: see `fork` instead.
+#[derive(Debug)]
 pub struct Analyzer {
     //  The meta-data is owned, to ensure it is not modified behind the analyzer's back.
     possible_values: PossibleValues,
     //  Journal of refinements.
     refinements: JournalWriter<Refinement>,
+    //  Refinements staged by the latest analysis, to be appended to the journal along with their premises.
+    staged: JournalWriter<Refinement>,
+    //  Tracker of the premises of the refinements.
+    tracker: PremiseTracker,
     //  Cursor over placements.
     placements_cursors: JournalMultiCursor<Placement, NUMBER_CURSORS>,
     //  Cursor over refinements.
     refinements_cursors: JournalMultiCursor<Refinement, NUMBER_CURSORS>,
+    //  Whether the grid is assumed to have a unique solution.
+    uniqueness: bool,
+    //  Which analyses to run, in which order, and within which limits.
+    config: AnalyzerConfig,
     //  Analyses.
-    cell_exclusion: CellExclusion,
-    group_exclusion: GroupExclusion,
-    group_inclusion: GroupInclusion,
-    group_overlap: GroupOverlap,
-    group_subset_inclusion: GroupSubsetInclusion,
+    analyses: Analyses,
 }
 
 impl Analyzer {
@@ -31,13 +66,36 @@ impl Analyzer {
         Self {
             possible_values: PossibleValues::all(),
             refinements,
+            staged: JournalWriter::new(),
+            tracker: PremiseTracker::default(),
             placements_cursors: JournalMultiCursor::new(placements),
             refinements_cursors,
-            cell_exclusion: CellExclusion,
-            group_exclusion: GroupExclusion,
-            group_inclusion: GroupInclusion,
-            group_overlap: GroupOverlap,
-            group_subset_inclusion: GroupSubsetInclusion,
+            uniqueness: false,
+            config: AnalyzerConfig::default(),
+            analyses: Analyses::default(),
+        }
+    }
+
+    /// Creates an independent copy, over the specified placements, with its own copy of the refinements so far.
+    pub fn fork(&self, placements: JournalReader<Placement>) -> Self {
+        let refinements = JournalWriter::from(self.refinements.reader().get_events());
+
+        let mut placements_cursors = JournalMultiCursor::new(placements);
+        placements_cursors.seek(self.placements_cursors.positions());
+
+        let mut refinements_cursors = JournalMultiCursor::new(refinements.reader());
+        refinements_cursors.seek(self.refinements_cursors.positions());
+
+        Self {
+            possible_values: self.possible_values,
+            refinements,
+            staged: JournalWriter::new(),
+            tracker: self.tracker.clone(),
+            placements_cursors,
+            refinements_cursors,
+            uniqueness: self.uniqueness,
+            config: self.config.clone(),
+            analyses: self.analyses.clone(),
         }
     }
 
@@ -47,20 +105,129 @@ impl Analyzer {
     /// Returns a handle over the refinements.
     pub fn refinements(&self) -> JournalReader<Refinement> { self.refinements.reader() }
 
+    /// Assumes the grid has a unique solution, enabling the analyses which require it.
+    ///
+    /// The givens are the digits of the original problem, which some analyses need to tell apart from placements.
+    pub fn assume_uniqueness(&mut self, givens: Grid) {
+        self.uniqueness = true;
+        self.analyses.avoidable_rectangle = AvoidableRectangle::new(givens);
+    }
+
+    /// Eliminates a candidate on behalf of the user.
+    ///
+    /// Returns whether the digit was still possible, and thus removed.
+    pub fn eliminate(&mut self, cell: CellIndex, digit: Digit) -> bool {
+        let possible_values = self.possible_values;
+
+        remove_possibility(&mut self.possible_values, &self.staged, cell, digit, &RefinementReason::User);
+
+        self.commit(possible_values) != 0
+    }
+
+    /// Refutes the candidate, by propagating singles from its assumption.
+    ///
+    /// All the placements so far are taken into account, even those not yet analyzed.
+    ///
+    /// Returns the branch reaching a contradiction, if any.
+    pub fn refute(&self, candidate: Candidate) -> Option<ForcingBranch> {
+        let mut possible_values = self.possible_values;
+
+        for placement in self.placements_cursors.reader().get_events() {
+            let (cell, digit) = (placement.cell(), placement.digit());
+
+            possible_values.resolve(cell, digit);
+
+            for peer in CellSet::peers(cell) {
+                possible_values.remove_possibility(peer, digit);
+            }
+        }
+
+        let propagation = Propagation::new(&possible_values, candidate);
+
+        propagation.is_contradiction().then(|| propagation.branch())
+    }
+
+    /// Returns a snapshot of the current state, to restore it later on.
+    pub fn snapshot(&self) -> AnalyzerSnapshot {
+        AnalyzerSnapshot {
+            possible_values: self.possible_values,
+            refinements: self.refinements.reader().len(),
+            placements_cursors: self.placements_cursors.positions(),
+            refinements_cursors: self.refinements_cursors.positions(),
+        }
+    }
+
+    /// Restores a snapshot, truncating the refinements to their length at the time.
+    ///
+    /// The placements are not truncated, as they belong to the Placer.
+    ///
+    /// Returns the refinements removed, to be appended anew should the snapshot be redone.
+    pub fn restore(&mut self, snapshot: &AnalyzerSnapshot) -> Vec<Refinement> {
+        self.possible_values = snapshot.possible_values;
+        self.placements_cursors.seek(snapshot.placements_cursors);
+        self.refinements_cursors.seek(snapshot.refinements_cursors);
+        self.tracker.reset();
+
+        self.refinements.truncate(snapshot.refinements)
+    }
+
+    /// Appends refinements anew, as returned by `restore`, prior to restoring a later snapshot.
+    pub fn reappend(&mut self, refinements: Vec<Refinement>) {
+        self.tracker.reset();
+        self.refinements.append_events(refinements);
+    }
+
+    /// Returns the configuration of the analyses.
+    pub fn config(&self) -> &AnalyzerConfig { &self.config }
+
+    /// Configures the analyses: which to run, in which order, and within which limits.
+    ///
+    /// Analyses enabled anew catch up with the placements and refinements made so far.
+    pub fn configure(&mut self, config: AnalyzerConfig) {
+        self.analyses.configure(&config);
+        self.config = config;
+    }
+
     /// Returns whether the Analyzer is done with analyses so far.
     pub fn is_done(&self) -> bool {
-        ALL_ANALYSES
-            .into_iter()
-            .map(Self::cursor_index)
-            .all(|index| self.placements_cursors.is_done(index) && self.refinements_cursors.is_done(index))
+        self.config.analyses()
+            .iter()
+            .copied()
+            .filter(|analysis| self.is_enabled(*analysis))
+            .all(|analysis| self.is_done_with(analysis))
     }
 
     /// Incrementally analyze placements and refinements until a refinement is produced or the analyzer has caught up
     /// with the end of the journals.
     ///
+    /// Global analyses, which scan the whole grid rather than the latest events, only run once all the analyses
+    /// preceding them have caught up, so that the cheapest possible explanation is always favored.
+    ///
     /// Returns the number of refinements added, possibly 0 if no progress was made.
     pub fn analyze(&mut self) -> usize {
-        for analysis in ALL_ANALYSES {
+        let mut caught_up = true;
+
+        for position in 0..self.config.analyses().len() {
+            let analysis = self.config.analyses()[position];
+
+            if !self.is_enabled(analysis) {
+                continue;
+            }
+
+            if analysis.is_global() {
+                if !caught_up {
+                    return 0;
+                }
+
+                let refined = self.analyze_grid_with(analysis);
+
+                if refined != 0 {
+                    return refined;
+                }
+
+                continue;
+            }
+
             let refined = self.analyze_next_placement_with(analysis);
 
             if refined != 0 {
@@ -72,6 +239,8 @@ impl Analyzer {
             if refined != 0 {
                 return refined;
             }
+
+            caught_up = caught_up && self.is_done_with(analysis);
         }
 
         0
@@ -82,23 +251,16 @@ impl Analyzer {
     /// Returns the number of refinements added, possibly 0 if no progress was made.
     pub fn analyze_next_placement_with(&mut self, analysis: Analysis) -> usize {
         let cursor_index = Self::cursor_index(analysis);
-        let before = self.refinements_cursors.reader().len();
+        let before = self.possible_values;
 
         let possible_values = &mut self.possible_values;
-        let refinements = &self.refinements;
-
-        let analysis: &mut dyn AnalysisImpl = match analysis {
-            Analysis::CellExclusion => &mut self.cell_exclusion,
-            Analysis::GroupExclusion => &mut self.group_exclusion,
-            Analysis::GroupInclusion => &mut self.group_inclusion,
-            Analysis::GroupOverlap => &mut self.group_overlap,
-            Analysis::GroupSubsetInclusion => &mut self.group_subset_inclusion,
-        };
+        let refinements = &self.staged;
+        let analysis = self.analyses.get_mut(analysis);
 
         self.placements_cursors.handle_next(cursor_index,
             |placement| analysis.analyze_next_placement(possible_values, refinements, placement));
 
-        self.refinements_cursors.reader().len() - before
+        self.commit(before)
     }
 
     /// Incrementally analyze one refinement with the specified analysis strategy.
@@ -106,26 +268,52 @@ impl Analyzer {
     /// Returns the number of refinements added, possibly 0 if no progress was made.
     pub fn analyze_next_refinement_with(&mut self, analysis: Analysis) -> usize {
         let cursor_index = Self::cursor_index(analysis);
-        let before = self.refinements_cursors.reader().len();
+        let before = self.possible_values;
 
         let possible_values = &mut self.possible_values;
-        let refinements = &self.refinements;
-
-        let analysis: &mut dyn AnalysisImpl = match analysis {
-            Analysis::CellExclusion => &mut self.cell_exclusion,
-            Analysis::GroupExclusion => &mut self.group_exclusion,
-            Analysis::GroupInclusion => &mut self.group_inclusion,
-            Analysis::GroupOverlap => &mut self.group_overlap,
-            Analysis::GroupSubsetInclusion => &mut self.group_subset_inclusion,
-        };
+        let refinements = &self.staged;
+        let analysis = self.analyses.get_mut(analysis);
 
         self.refinements_cursors.handle_next(cursor_index,
             |refinement| analysis.analyze_next_refinement(possible_values, refinements, refinement));
 
-        self.refinements_cursors.reader().len() - before
+        self.commit(before)
+    }
+
+    /// Analyze the whole grid with the specified analysis strategy, if any event occurred since the last analysis.
+    ///
+    /// Returns the number of refinements added, possibly 0 if no progress was made.
+    pub fn analyze_grid_with(&mut self, analysis: Analysis) -> usize {
+        let cursor_index = Self::cursor_index(analysis);
+        let before = self.possible_values;
+
+        let skipped = self.placements_cursors.catch_up(cursor_index) + self.refinements_cursors.catch_up(cursor_index);
+
+        if skipped == 0 {
+            return 0;
+        }
+
+        self.analyses.analyze_grid(analysis, &mut self.possible_values, &self.staged);
+
+        self.commit(before)
     }
 }
 
+/// A snapshot of the state of the Analyzer.
+///
+/// The state of the analyses themselves is not captured: it is either configuration, or caches derived from the
+/// possible values.
+#[derive(Clone, Debug)]
+pub struct AnalyzerSnapshot {
+    possible_values: PossibleValues,
+    //  Number of refinements.
+    refinements: usize,
+    //  Positions of the cursors over placements.
+    placements_cursors: [usize; NUMBER_CURSORS],
+    //  Positions of the cursors over refinements.
+    refinements_cursors: [usize; NUMBER_CURSORS],
+}
+
 //
 //  Implementation Details
 //
@@ -134,12 +322,222 @@ const NUMBER_CURSORS: usize = NUMBER_ANALYSIS;
 
 impl Analyzer {
     fn cursor_index(analysis: Analysis) -> usize { analysis as u8 as usize }
+
+    fn is_enabled(&self, analysis: Analysis) -> bool {
+        self.config.is_enabled(analysis) && (self.uniqueness || !analysis.requires_uniqueness())
+    }
+
+    fn is_done_with(&self, analysis: Analysis) -> bool {
+        let index = Self::cursor_index(analysis);
+
+        self.placements_cursors.is_done(index) && self.refinements_cursors.is_done(index)
+    }
+
+    //  Appends the staged refinements to the journal, with their premises, given the possible values prior to them.
+    //
+    //  Returns the number of refinements appended.
+    fn commit(&mut self, possible_values: PossibleValues) -> usize {
+        let staged = self.staged.truncate(0);
+        let number = staged.len();
+
+        if number != 0 {
+            let refinements = self.refinements.reader();
+            let staged = self.tracker.annotate(staged, &possible_values, &refinements, self.placements_cursors.reader());
+
+            self.refinements.append_events(staged);
+        }
+
+        number
+    }
+}
+
+//  Internal: removes a possible digit from a cell, recording the refinement.
+//
+//  Returns whether the digit was still possible, and thus removed.
+fn remove_possibility(
+    possible_values: &mut PossibleValues,
+    refinements: &JournalWriter<Refinement>,
+    cell: CellIndex,
+    digit: Digit,
+    reason: &RefinementReason,
+)
+    -> bool
+{
+    if possible_values.remove_possibility(cell, digit).is_none() {
+        return false;
+    }
+
+    #[cfg(debug_assertions)]
+    eprintln!("Analyzer - Remove {digit:?} from {cell:?} ({:?}/{:?}) due to {reason:?}", cell.row(), cell.column());
+
+    refinements.append_event(Refinement::new(cell, digit, reason.clone()));
+
+    true
+}
+
+//  Internal: removes a possible digit from all the target cells, recording the refinements.
+//
+//  Returns whether the digit was still possible in any of the targets, and thus removed.
+fn remove_possibilities(
+    possible_values: &mut PossibleValues,
+    refinements: &JournalWriter<Refinement>,
+    targets: CellSet,
+    digit: Digit,
+    reason: &RefinementReason,
+)
+    -> bool
+{
+    let mut removed = false;
+
+    for target in targets {
+        removed |= remove_possibility(possible_values, refinements, target, digit, reason);
+    }
+
+    removed
+}
+
+//  Internal: returns the cell at the specified 0-based row and column.
+fn cell_at(row: usize, column: usize) -> CellIndex {
+    CellIndex::from_coordinates(RowIndex::new(row).expect("Valid row"), ColumnIndex::new(column).expect("Valid column"))
+}
+
+//  Internal: enumerates the bitmasks with exactly `k` of the `n` lowest bits set, in increasing order.
+fn combinations(n: usize, k: usize) -> impl Iterator<Item = u32> {
+    let end = 1u32 << n;
+    let first = if k <= n { Some((1u32 << k) - 1) } else { None };
+
+    std::iter::successors(first, move |&mask| {
+        if mask == 0 {
+            return None;
+        }
+
+        //  Gosper's hack: the next greater integer with the same number of bits set.
+        let lowest = mask & mask.wrapping_neg();
+        let ripple = mask + lowest;
+        let next = (((ripple ^ mask) >> 2) / lowest) | ripple;
+
+        (next < end).then_some(next)
+    })
+}
+
+//  Internal: selects the items whose index is set within the bitmask, in order.
+fn select<T: Copy>(items: &[T], mask: u32) -> impl Iterator<Item = T> + '_ {
+    items.iter()
+        .enumerate()
+        .filter(move |(index, _)| mask & (1 << index) != 0)
+        .map(|(_, item)| *item)
 }
 
 //
 //  Analyses
 //
 
+#[derive(Clone, Debug, Default)]
+struct Analyses {
+    cell_exclusion: CellExclusion,
+    group_exclusion: GroupExclusion,
+    group_inclusion: GroupInclusion,
+    group_overlap: GroupOverlap,
+    group_subset_inclusion: GroupSubsetInclusion,
+    fish: Fish,
+    finned_fish: FinnedFish,
+    x_cycle: XCycle,
+    bivalue_universal_grave: BivalueUniversalGrave,
+    unique_rectangle: UniqueRectangle,
+    avoidable_rectangle: AvoidableRectangle,
+    sue_de_coq: SueDeCoq,
+    almost_locked_set_xz: AlmostLockedSetXZ,
+    almost_locked_set_xy_wing: AlmostLockedSetXYWing,
+    almost_locked_set_chain: AlmostLockedSetChain,
+    death_blossom: DeathBlossom,
+    //  The index of the ALSs, shared by the ALS analyses.
+    almost_locked_sets: AlmostLockedSetIndex,
+    exocet: Exocet,
+    medusa: Medusa,
+    alternating_inference_chain: AlternatingInferenceChain,
+    franken_fish: FrankenFish,
+    nishio: Nishio,
+    pattern_overlay: PatternOverlay,
+    forcing_chain: ForcingChain,
+    bowmans_bingo: BowmansBingo,
+}
+
+impl Analyses {
+    //  Applies the limits of the configuration.
+    fn configure(&mut self, config: &AnalyzerConfig) {
+        self.group_subset_inclusion = GroupSubsetInclusion::new(config.max_subset_size());
+        self.fish = Fish::new(config.max_fish_size());
+        self.finned_fish = FinnedFish::new(config.max_fish_size());
+        self.franken_fish = FrankenFish::new(config.max_franken_fish_size(), config.max_franken_fish_fins());
+        self.x_cycle = XCycle::new(config.max_chain_length());
+        self.alternating_inference_chain = AlternatingInferenceChain::new(config.max_chain_length());
+        self.nishio = Nishio::new(config.nishio_depth(), config.nishio_effort());
+        self.forcing_chain = ForcingChain::new(config.max_forcing_branches());
+        self.bowmans_bingo = BowmansBingo::new(config.max_guess_depth());
+    }
+
+    //  Analyzes the whole grid, the ALS analyses sharing the index of the ALSs, updated once per change to the possible
+    //  values.
+    fn analyze_grid(
+        &mut self,
+        analysis: Analysis,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        let index = &mut self.almost_locked_sets;
+
+        match analysis {
+            Analysis::AlmostLockedSetXZ => {
+                index.update(possible_values);
+                self.almost_locked_set_xz.analyze_grid(index, possible_values, refinements);
+            },
+            Analysis::AlmostLockedSetXYWing => {
+                index.update(possible_values);
+                self.almost_locked_set_xy_wing.analyze_grid(index, possible_values, refinements);
+            },
+            Analysis::AlmostLockedSetChain => {
+                index.update(possible_values);
+                self.almost_locked_set_chain.analyze_grid(index, possible_values, refinements);
+            },
+            Analysis::DeathBlossom => {
+                index.update(possible_values);
+                self.death_blossom.analyze_grid(index, possible_values, refinements);
+            },
+            _ => self.get_mut(analysis).analyze_grid(possible_values, refinements),
+        }
+    }
+
+    fn get_mut(&mut self, analysis: Analysis) -> &mut dyn AnalysisImpl {
+        match analysis {
+            Analysis::CellExclusion => &mut self.cell_exclusion,
+            Analysis::GroupExclusion => &mut self.group_exclusion,
+            Analysis::GroupInclusion => &mut self.group_inclusion,
+            Analysis::GroupOverlap => &mut self.group_overlap,
+            Analysis::GroupSubsetInclusion => &mut self.group_subset_inclusion,
+            Analysis::Fish => &mut self.fish,
+            Analysis::FinnedFish => &mut self.finned_fish,
+            Analysis::XCycle => &mut self.x_cycle,
+            Analysis::BivalueUniversalGrave => &mut self.bivalue_universal_grave,
+            Analysis::UniqueRectangle => &mut self.unique_rectangle,
+            Analysis::AvoidableRectangle => &mut self.avoidable_rectangle,
+            Analysis::SueDeCoq => &mut self.sue_de_coq,
+            Analysis::AlmostLockedSetXZ => &mut self.almost_locked_set_xz,
+            Analysis::AlmostLockedSetXYWing => &mut self.almost_locked_set_xy_wing,
+            Analysis::AlmostLockedSetChain => &mut self.almost_locked_set_chain,
+            Analysis::DeathBlossom => &mut self.death_blossom,
+            Analysis::Exocet => &mut self.exocet,
+            Analysis::Medusa => &mut self.medusa,
+            Analysis::AlternatingInferenceChain => &mut self.alternating_inference_chain,
+            Analysis::FrankenFish => &mut self.franken_fish,
+            Analysis::Nishio => &mut self.nishio,
+            Analysis::PatternOverlay => &mut self.pattern_overlay,
+            Analysis::ForcingChain => &mut self.forcing_chain,
+            Analysis::BowmansBingo => &mut self.bowmans_bingo,
+        }
+    }
+}
+
 trait AnalysisImpl {
     #[allow(unused_variables )]
     fn analyze_next_placement(
@@ -160,6 +558,16 @@ trait AnalysisImpl {
     )
     {
     }
+
+    //  Only invoked for global analyses, see `Analysis::is_global`.
+    #[allow(unused_variables )]
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -240,8 +648,7 @@ impl AnalysisImpl for GroupInclusion {
             }
 
             let candidate = group.cells().into_iter()
-                .filter(|candidate| possible_values.of_cell(*candidate).has(digit))
-                .next()
+                .find(|candidate| possible_values.of_cell(*candidate).has(digit))
                 .expect("1 cell, as per the count above");
 
             for removed in possible_values.resolve(candidate, digit) {
@@ -323,97 +730,167 @@ impl AnalysisImpl for GroupOverlap {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-struct GroupSubsetInclusion;
+#[derive(Clone, Debug)]
+struct GroupSubsetInclusion {
+    max_size: usize,
+}
 
 impl GroupSubsetInclusion {
-    //  Search for all cells with the same subset of possible digits as the argument.
+    fn new(max_size: usize) -> Self { Self { max_size } }
+
+    //  Search the group for sets of N cells whose possible values are limited to the same N digits.
+    //
+    //  Every subset of N of the digits left within the group is enumerated, hence none is missed; a hidden subset is
+    //  found as the complementary naked subset.
+    //
+    //  Returns whether any possibility was removed.
     //
     //  #   Complexity
     //
-    //  Quadratic (time) in the number of cells per group.
-    fn analyze_next_cell(
-        &mut self,
+    //  Linear (time) in the number of cells per group, for each subset of N of the digits left.
+    fn analyze_group(
+        &self,
         possible_values: &mut PossibleValues,
         refinements: &JournalWriter<Refinement>,
-        cell: CellIndex,
+        group: Group,
+        size: usize,
     )
+        -> bool
     {
-        let digits_subset = possible_values.of_cell(cell);
+        let unsolved: Vec<CellIndex> = group.cells()
+            .into_iter()
+            .filter(|cell| possible_values.of_cell(*cell).size() > 1)
+            .collect();
 
-        //  GroupInclusion will handle that case very well, and cheaper.
-        if digits_subset.size() <= 1 {
-            return;
-        }
+        let digits: Vec<Digit> = unsolved.iter()
+            .fold(DigitSet::default(), |digits, cell| digits.union(&possible_values.of_cell(*cell)))
+            .into_iter()
+            .collect();
 
-        //  Let's limit to subsets of 2, 3, or 4 elements, to avoid complexity running away from us.
-        if digits_subset.size() > DIMENSION / 2 {
-            return;
+        //  All cells remove nothing.
+        if size >= unsolved.len() {
+            return false;
         }
 
-        for group in Group::groups(cell) {
-            let mut cells_subset = CellSet::from(cell);
-
-            for candidate in group.cells() {
-                if candidate == cell {
-                    continue;
-                }
+        for mask in combinations(digits.len(), size) {
+            let digits_subset: DigitSet = select(&digits, mask).collect();
 
-                if possible_values.of_cell(candidate).is_subset_of(&digits_subset) {
-                    cells_subset.add(candidate);
-                }
-            }
+            let cells_subset: CellSet = unsolved.iter()
+                .copied()
+                .filter(|cell| possible_values.of_cell(*cell).is_subset_of(&digits_subset))
+                .collect();
 
-            if cells_subset.size() != digits_subset.size() {
+            if cells_subset.size() != size {
                 continue;
             }
 
-            for candidate in group.cells() {
-                if cells_subset.has(candidate) {
-                    continue;
-                }
+            let reason = RefinementReason::GroupSubsetInclusion(cells_subset, digits_subset, group);
+            let mut removed = false;
 
+            for candidate in unsolved.iter().copied().filter(|cell| !cells_subset.has(*cell)) {
                 for digit in digits_subset {
-                    if possible_values.of_cell(candidate).has(digit) {
-                        #[cfg(debug_assertions)]
-                        eprintln!("GroupSubsetInclusion::analyze - Remove {digit:?} from {candidate:?} ({:?}/{:?})",
-                            candidate.row(), candidate.column());
-
-                        let reason = RefinementReason::GroupSubsetInclusion(cells_subset, digits_subset, group);
-
-                        possible_values.remove_possibility(candidate, digit);
-                        refinements.append_event(Refinement::new(candidate, digit, reason));
-                    }
+                    removed |= remove_possibility(possible_values, refinements, candidate, digit, &reason);
                 }
             }
+
+            if removed {
+                return true;
+            }
         }
+
+        false
     }
 }
 
+impl Default for GroupSubsetInclusion {
+    fn default() -> Self { Self::new(DEFAULT_MAX_SUBSET_SIZE) }
+}
+
 impl AnalysisImpl for GroupSubsetInclusion {
-    fn analyze_next_refinement(
+    fn analyze_grid(
         &mut self,
         possible_values: &mut PossibleValues,
         refinements: &JournalWriter<Refinement>,
-        refinement: Refinement,
     )
     {
-        //  There are two possibilities for running the analysis:
-        //
-        //  -   Based on the cell: quadratic.
-        //  -   Based on the cells still containing the removed digit: cubic.
-
-        let cell = refinement.cell();
-        let digit = refinement.removed();
-
-        self.analyze_next_cell(possible_values, refinements, cell);
-
-        for group in Group::groups(cell) {
-            for cell in group.cells() {
-                if possible_values.of_cell(cell).has(digit) {
-                    self.analyze_next_cell(possible_values, refinements, cell);
+        //  Smallest first, over all groups; a single cell is handled by GroupInclusion.
+        for size in 2..=self.max_size {
+            for group in GroupIndex::all().map(Group::new) {
+                if self.analyze_group(possible_values, refinements, group, size) {
+                    return;
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates, row};
+
+#[test]
+fn group_subset_inclusion_smallest_first() {
+    //  r1c2, r1c3, r1c4, r1c6 and r1c8 form a naked quintuplet, but r4c2 and r4c3 form a naked pair.
+    let mut possible_values = parse_candidates("
+        257 2679 269 36 4 2369 1 2367 8
+        127 12679 3 16 8 1269 5 267 2467
+        128 126 4 7 5 1236 9 236 236
+        23 29 29 4 6 7 8 15 15
+        4 8 1 9 3 5 2 67 67
+        6 5 7 2 1 8 3 4 9
+        18 146 68 1356 2 1346 7 13589 135
+        127 1267 5 8 9 136 4 123 123
+        9 3 28 15 7 14 6 1258 125
+    ");
+    let refinements = JournalWriter::new();
+
+    GroupSubsetInclusion::default().analyze_grid(&mut possible_values, &refinements);
+
+    let cells = [cell(4, 2), cell(4, 3)].into_iter().collect();
+    let digits = [digit(2), digit(9)].into_iter().collect();
+    let reason = RefinementReason::GroupSubsetInclusion(cells, digits, row(4));
+
+    assert_eq!(vec![Refinement::new(cell(4, 1), digit(2), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn group_subset_inclusion_quintuplet() {
+    //  r1c1 to r1c5 form a naked quintuplet, and no smaller subset exists.
+    let candidates = "
+        1234 1235 1245 1345 2345 16789 26789 36789 46789
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+    ";
+
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    GroupSubsetInclusion::new(4).analyze_grid(&mut possible_values, &refinements);
+
+    assert_eq!(Vec::<Refinement>::new(), refinements.reader().get_events());
+
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    GroupSubsetInclusion::default().analyze_grid(&mut possible_values, &refinements);
+
+    let cells = (1..=5).map(|column| cell(1, column)).collect();
+    let digits = (1..=5).map(digit).collect();
+    let reason = RefinementReason::GroupSubsetInclusion(cells, digits, row(1));
+
+    let expected: Vec<Refinement> = (1..=4)
+        .map(|d| Refinement::new(cell(1, 5 + d), digit(d), reason.clone()))
+        .collect();
+
+    assert_eq!(expected, refinements.reader().get_events());
+}
+
+}
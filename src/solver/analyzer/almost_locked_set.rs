@@ -0,0 +1,721 @@
+//! Analyses based on Almost Locked Sets: ALS-XZ, ALS-XY-Wing, ALS chains, and Death Blossom.
+//!
+//! Two ALSs share a restricted common digit when all the cells of either which may hold this digit see all the cells of
+//! the other which may hold it, hence the digit can appear in at most one of the two ALSs.
+
+use std::sync::Arc;
+
+use crate::model::{DIMENSION, CellIndex, CellSet, Digit, DigitSet, Group, GroupIndex};
+use crate::solver::{AlmostLockedSet, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, combinations, remove_possibilities, select};
+
+/// The maximum number of cells of an ALS.
+pub(super) const MAX_ALMOST_LOCKED_SET_SIZE: usize = 5;
+
+/// The maximum number of ALSs within an ALS chain.
+pub(super) const MAX_ALMOST_LOCKED_SET_CHAIN_LENGTH: usize = 5;
+
+/// The maximum number of petals of a Death Blossom, that is of possible digits of its stem.
+pub(super) const MAX_DEATH_BLOSSOM_PETALS: usize = 3;
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct AlmostLockedSetXZ;
+
+//  Analyzed with the index shared by all ALS analyses, see `Analyses::analyze_grid`.
+impl AnalysisImpl for AlmostLockedSetXZ {}
+
+impl AlmostLockedSetXZ {
+    pub(super) fn analyze_grid(
+        &self,
+        index: &AlmostLockedSetIndex,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for (position, a) in index.entries.iter().enumerate() {
+            for &(other, restricted) in &index.links[position] {
+                if other < position {
+                    continue;
+                }
+
+                let b = &index.entries[other];
+
+                let reason = RefinementReason::AlmostLockedSetXZ(a.set, b.set, restricted);
+                let cells = a.set.cells().union(&b.set.cells());
+
+                let mut removed = false;
+
+                if restricted.size() == 1 {
+                    //  Singly linked: either ALS is locked, hence any other common digit is in either.
+                    for digit in a.set.digits().intersection(&b.set.digits()).difference(&restricted) {
+                        let targets = a.seen_by(digit).intersection(&b.seen_by(digit));
+
+                        removed |= remove_possibilities(possible_values, refinements, targets.difference(&cells), digit, &reason);
+                    }
+                } else {
+                    //  Doubly linked: each ALS holds one of the restricted commons, hence both are locked.
+                    for digit in restricted {
+                        let targets = a.seen_by(digit).intersection(&b.seen_by(digit));
+
+                        removed |= remove_possibilities(possible_values, refinements, targets.difference(&cells), digit, &reason);
+                    }
+
+                    for entry in [a, b] {
+                        for digit in entry.set.digits().difference(&restricted) {
+                            let targets = entry.seen_by(digit);
+
+                            removed |= remove_possibilities(possible_values, refinements, targets.difference(&cells), digit, &reason);
+                        }
+                    }
+                }
+
+                if removed {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct AlmostLockedSetXYWing;
+
+//  Analyzed with the index shared by all ALS analyses, see `Analyses::analyze_grid`.
+impl AnalysisImpl for AlmostLockedSetXYWing {}
+
+impl AlmostLockedSetXYWing {
+    pub(super) fn analyze_grid(
+        &self,
+        index: &AlmostLockedSetIndex,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for (position, pivot) in index.entries.iter().enumerate() {
+            //  The wings, with their restricted common with the pivot.
+            let wings: Vec<(&Entry, Digit)> = index.links[position].iter()
+                .flat_map(|&(wing, restricted)| restricted.into_iter().map(move |digit| (&index.entries[wing], digit)))
+                .collect();
+
+            for (position, &(a, x)) in wings.iter().enumerate() {
+                for &(b, y) in &wings[position + 1..] {
+                    if x == y || !a.set.cells().intersection(&b.set.cells()).is_empty() {
+                        continue;
+                    }
+
+                    let commons = a.set.digits().intersection(&b.set.digits()).difference(&[x, y].into_iter().collect());
+
+                    let reason = RefinementReason::AlmostLockedSetXYWing(a.set, b.set, pivot.set);
+
+                    let mut removed = false;
+
+                    for digit in commons {
+                        let targets = a.seen_by(digit).intersection(&b.seen_by(digit));
+
+                        removed |= remove_possibilities(possible_values, refinements, targets, digit, &reason);
+                    }
+
+                    if removed {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct AlmostLockedSetChain;
+
+//  Analyzed with the index shared by all ALS analyses, see `Analyses::analyze_grid`.
+impl AnalysisImpl for AlmostLockedSetChain {}
+
+impl AlmostLockedSetChain {
+    pub(super) fn analyze_grid(
+        &self,
+        index: &AlmostLockedSetIndex,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        //  Shorter chains are covered by ALS-XZ (2 ALSs) and ALS-XY-Wing (3 ALSs).
+        for length in 4..=MAX_ALMOST_LOCKED_SET_CHAIN_LENGTH {
+            for start in 0..index.entries.len() {
+                let mut chain = ChainSearch { index, length, path: vec![start], restricted: Vec::new() };
+
+                if chain.extend(possible_values, refinements) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct DeathBlossom;
+
+//  Analyzed with the index shared by all ALS analyses, see `Analyses::analyze_grid`.
+impl AnalysisImpl for DeathBlossom {}
+
+impl DeathBlossom {
+    pub(super) fn analyze_grid(
+        &self,
+        index: &AlmostLockedSetIndex,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for size in 2..=MAX_DEATH_BLOSSOM_PETALS {
+            for stem in CellIndex::all() {
+                let digits = possible_values.of_cell(stem);
+
+                if digits.size() != size {
+                    continue;
+                }
+
+                //  For each digit of the stem, the petals: ALSs all the cells of which holding the digit see the stem.
+                let peers = CellSet::peers(stem);
+
+                let petals: Vec<(Digit, Vec<&Entry>)> = digits.into_iter()
+                    .map(|digit| {
+                        let petals = index.entries.iter()
+                            .filter(|entry| !entry.set.cells().has(stem) && entry.set.digits().has(digit))
+                            .filter(|entry| entry.cells_of(digit).is_subset_of(&peers))
+                            .collect();
+
+                        (digit, petals)
+                    })
+                    .collect();
+
+                let mut blossom = BlossomSearch { stem, stem_digits: digits, petals: &petals, chosen: Vec::new() };
+
+                if blossom.extend(possible_values, refinements) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  Index of all the ALSs of the grid, smallest first, with the restricted commons between them, shared by all the ALS
+//  analyses.
+//
+//  The index is updated incrementally: only the ALSs of the groups in which possible values changed are enumerated
+//  anew, and only the restricted commons involving a new ALS are computed anew.
+#[derive(Clone, Debug, Default)]
+pub(super) struct AlmostLockedSetIndex {
+    //  The possible values as of the last update, if any.
+    possible_values: Option<PossibleValues>,
+    //  The ALSs of each group.
+    groups: Vec<Vec<AlmostLockedSet>>,
+    entries: Vec<Entry>,
+    //  For each entry, the other entries sharing restricted commons with it, and those restricted commons.
+    links: Vec<Vec<(usize, DigitSet)>>,
+}
+
+impl AlmostLockedSetIndex {
+    pub(super) fn update(&mut self, possible_values: &PossibleValues) {
+        let changed: CellSet = match self.possible_values {
+            Some(previous) => CellIndex::all().filter(|cell| previous.of_cell(*cell) != possible_values.of_cell(*cell)).collect(),
+            None => CellSet::full(),
+        };
+
+        if changed.is_empty() {
+            return;
+        }
+
+        self.groups.resize(GroupIndex::all().count(), Vec::new());
+
+        for (group, sets) in GroupIndex::all().map(Group::new).zip(self.groups.iter_mut()) {
+            if group.cells().into_iter().any(|cell| changed.has(cell)) {
+                *sets = Self::enumerate(possible_values, group);
+            }
+        }
+
+        //  An ALS within a square and a line is found twice.
+        let mut sets: Vec<AlmostLockedSet> = self.groups.iter().flatten().copied().collect();
+        sets.sort_by_key(sort_key);
+        sets.dedup();
+
+        //  Maps the position of each new entry to the position of the previous entry it is identical to, if any.
+        let previous: Vec<Option<usize>> = sets.iter()
+            .map(|set| {
+                if !set.cells().intersection(&changed).is_empty() {
+                    return None;
+                }
+
+                self.entries.binary_search_by_key(&sort_key(set), |entry| sort_key(&entry.set)).ok()
+            })
+            .collect();
+
+        let mut reverse = vec![None; self.entries.len()];
+
+        for (position, previous) in previous.iter().enumerate() {
+            if let Some(previous) = previous {
+                reverse[*previous] = Some(position);
+            }
+        }
+
+        let peers: Vec<CellSet> = CellIndex::all().map(CellSet::peers).collect();
+
+        let entries: Vec<Entry> = sets.into_iter()
+            .zip(&previous)
+            .map(|(set, previous)| match previous {
+                Some(previous) => self.entries[*previous].clone(),
+                None => Entry::new(set, possible_values, &peers),
+            })
+            .collect();
+
+        let mut links = vec![Vec::new(); entries.len()];
+
+        for (position, entry) in entries.iter().enumerate() {
+            //  Links between previous entries are carried over.
+            if let Some(previous) = previous[position] {
+                for &(other, restricted) in &self.links[previous] {
+                    if let Some(other) = reverse[other] {
+                        links[position].push((other, restricted));
+                    }
+                }
+            }
+
+            for (other, other_entry) in entries.iter().enumerate().skip(position + 1) {
+                if previous[position].is_some() && previous[other].is_some() {
+                    continue;
+                }
+
+                let restricted = entry.restricted_commons(other_entry);
+
+                if !restricted.is_empty() {
+                    links[position].push((other, restricted));
+                    links[other].push((position, restricted));
+                }
+            }
+        }
+
+        self.possible_values = Some(*possible_values);
+        self.entries = entries;
+        self.links = links;
+    }
+
+    fn enumerate(possible_values: &PossibleValues, group: Group) -> Vec<AlmostLockedSet> {
+        let unsolved: Vec<CellIndex> = group.cells()
+            .into_iter()
+            .filter(|cell| possible_values.of_cell(*cell).size() > 1)
+            .collect();
+
+        let mut sets = Vec::new();
+
+        for size in 1..=MAX_ALMOST_LOCKED_SET_SIZE.min(unsolved.len()) {
+            for mask in combinations(unsolved.len(), size) {
+                let cells: CellSet = select(&unsolved, mask).collect();
+
+                let digits = cells.into_iter()
+                    .fold(DigitSet::default(), |digits, cell| digits.union(&possible_values.of_cell(cell)));
+
+                if digits.size() == size + 1 {
+                    sets.push(AlmostLockedSet::new(cells, digits));
+                }
+            }
+        }
+
+        sets
+    }
+}
+
+fn sort_key(set: &AlmostLockedSet) -> (usize, AlmostLockedSet) { (set.cells().size(), *set) }
+
+//  An ALS, and pre-computed information about it.
+#[derive(Clone, Debug)]
+struct Entry {
+    set: AlmostLockedSet,
+    //  For each digit, the cells of the set which may hold it.
+    cells_of: [CellSet; DIMENSION],
+    //  For each digit, the cells which see all the cells of the set which may hold it.
+    seen_by: [CellSet; DIMENSION],
+}
+
+impl Entry {
+    fn new(set: AlmostLockedSet, possible_values: &PossibleValues, peers: &[CellSet]) -> Self {
+        let mut cells_of = [CellSet::empty(); DIMENSION];
+        let mut seen_by = [CellSet::empty(); DIMENSION];
+
+        for digit in set.digits() {
+            let index = digit.value() - 1;
+
+            cells_of[index] = set.cells().into_iter().filter(|cell| possible_values.of_cell(*cell).has(digit)).collect();
+            seen_by[index] = cells_of[index].into_iter().fold(CellSet::full(), |seen, cell| seen.intersection(&peers[cell.value()]));
+        }
+
+        Self { set, cells_of, seen_by }
+    }
+
+    fn cells_of(&self, digit: Digit) -> CellSet { self.cells_of[digit.value() - 1] }
+
+    fn seen_by(&self, digit: Digit) -> CellSet { self.seen_by[digit.value() - 1] }
+
+    //  Returns the restricted common digits of two non-overlapping ALSs.
+    fn restricted_commons(&self, other: &Entry) -> DigitSet {
+        if !self.set.cells().intersection(&other.set.cells()).is_empty() {
+            return DigitSet::default();
+        }
+
+        self.set.digits()
+            .intersection(&other.set.digits())
+            .into_iter()
+            .filter(|digit| other.cells_of(*digit).is_subset_of(&self.seen_by(*digit)))
+            .collect()
+    }
+}
+
+//  The search for an ALS chain of a given length.
+//
+//  Should the first ALS not hold a digit Z, it is locked, and holds the first restricted common, which the second ALS
+//  thus cannot hold, and so on until the last ALS, which must then hold Z. Hence Z is in either the first or last ALS.
+struct ChainSearch<'a> {
+    index: &'a AlmostLockedSetIndex,
+    length: usize,
+    //  The positions of the ALSs of the chain so far.
+    path: Vec<usize>,
+    //  The restricted commons between consecutive ALSs of the chain so far.
+    restricted: Vec<Digit>,
+}
+
+impl ChainSearch<'_> {
+    //  Returns whether any possibility was removed.
+    fn extend(&mut self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        if self.path.len() == self.length {
+            return self.eliminate(possible_values, refinements);
+        }
+
+        let last = *self.path.last().expect("Non-empty path");
+        let index = self.index;
+
+        for &(next, restricted) in &index.links[last] {
+            if self.path.contains(&next) {
+                continue;
+            }
+
+            //  Consecutive restricted commons must differ, as the ALS between them only loses one digit.
+            for digit in restricted {
+                if self.restricted.last() == Some(&digit) {
+                    continue;
+                }
+
+                self.path.push(next);
+                self.restricted.push(digit);
+
+                let removed = self.extend(possible_values, refinements);
+
+                self.path.pop();
+                self.restricted.pop();
+
+                if removed {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn eliminate(&self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        let first = &self.index.entries[self.path[0]];
+        let last = &self.index.entries[*self.path.last().expect("Non-empty path")];
+
+        let excluded: DigitSet = [self.restricted[0], *self.restricted.last().expect("Non-empty chain")].into_iter().collect();
+        let commons = first.set.digits().intersection(&last.set.digits()).difference(&excluded);
+
+        if commons.is_empty() {
+            return false;
+        }
+
+        let sets: Arc<[AlmostLockedSet]> = self.path.iter().map(|position| self.index.entries[*position].set).collect();
+        let reason = RefinementReason::AlmostLockedSetChain(sets, self.restricted.iter().copied().collect());
+
+        let mut removed = false;
+
+        for digit in commons {
+            let targets = first.seen_by(digit).intersection(&last.seen_by(digit));
+
+            removed |= remove_possibilities(possible_values, refinements, targets, digit, &reason);
+        }
+
+        removed
+    }
+}
+
+//  The search for a Death Blossom around a given stem.
+//
+//  The stem holds one of its digits, hence the petal attached to this digit cannot hold it, and is locked. A digit Z
+//  common to all the petals, and not possible in the stem, is thus in one of the petals.
+struct BlossomSearch<'a> {
+    stem: CellIndex,
+    stem_digits: DigitSet,
+    //  For each digit of the stem, the candidate petals.
+    petals: &'a [(Digit, Vec<&'a Entry>)],
+    //  The petals chosen so far, one per digit of the stem.
+    chosen: Vec<&'a Entry>,
+}
+
+impl BlossomSearch<'_> {
+    //  Returns whether any possibility was removed.
+    fn extend(&mut self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        if self.chosen.len() == self.petals.len() {
+            return self.eliminate(possible_values, refinements);
+        }
+
+        let petals = self.petals;
+
+        for petal in &petals[self.chosen.len()].1 {
+            self.chosen.push(petal);
+
+            //  Prune as soon as no digit could be eliminated.
+            let removed = !self.targets().iter().all(|(_, targets)| targets.is_empty())
+                && self.extend(possible_values, refinements);
+
+            self.chosen.pop();
+
+            if removed {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //  Returns, for each digit common to the petals chosen so far, the cells seeing all of them which may hold it.
+    fn targets(&self) -> Vec<(Digit, CellSet)> {
+        let commons = self.chosen.iter()
+            .fold(DigitSet::full(), |commons, petal| commons.intersection(&petal.set.digits()))
+            .difference(&self.stem_digits);
+
+        let cells = self.chosen.iter().fold(CellSet::from(self.stem), |cells, petal| cells.union(&petal.set.cells()));
+
+        commons.into_iter()
+            .map(|digit| {
+                let targets = self.chosen.iter()
+                    .fold(CellSet::full(), |targets, petal| targets.intersection(&petal.seen_by(digit)))
+                    .difference(&cells);
+
+                (digit, targets)
+            })
+            .collect()
+    }
+
+    fn eliminate(&self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        let petals: Arc<[(Digit, AlmostLockedSet)]> = self.petals.iter()
+            .zip(&self.chosen)
+            .map(|((digit, _), petal)| (*digit, petal.set))
+            .collect();
+
+        let reason = RefinementReason::DeathBlossom(self.stem, petals);
+
+        let mut removed = false;
+
+        for (digit, targets) in self.targets() {
+            removed |= remove_possibilities(possible_values, refinements, targets, digit, &reason);
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+const GRID: &str = "
+    4 89 58 6 35 7 1 389 2
+    1 2 3 9 8 4 56 7 56
+    57 789 6 135 235 1235 39 4 389
+    3 1 25 4 9 25 8 6 7
+    6 78 9 3578 2357 2358 4 235 1
+    57 4 258 13578 6 12358 2359 2359 359
+    2 3 1 578 57 6 579 589 4
+    8 5 4 37 1 9 2367 23 36
+    9 6 7 2 4 358 35 1 358
+";
+
+#[test]
+fn singly_linked_xz() {
+    //  r1c5 and r7c5 + r8c4 are linked by 5, hence either holds 3.
+    let (mut possible_values, index) = indexed(GRID);
+    let refinements = JournalWriter::new();
+
+    AlmostLockedSetXZ.analyze_grid(&index, &mut possible_values, &refinements);
+
+    let reason = RefinementReason::AlmostLockedSetXZ(
+        als(&[(1, 5)], &[3, 5]),
+        als(&[(7, 5), (8, 4)], &[3, 5, 7]),
+        DigitSet::from(digit(5)));
+
+    assert_eq!(vec![Refinement::new(cell(3, 4), digit(3), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn doubly_linked_xz() {
+    //  r4c6 and r5c2 + r5c4 + r5c5 + r5c6 are linked by both 2 and 5, hence both are locked.
+    let (mut possible_values, index) = indexed("
+        4 89 58 6 35 7 1 389 2
+        1 2 3 9 8 4 6 7 5
+        57 789 6 15 235 1235 39 4 389
+        3 1 25 4 9 25 8 6 7
+        6 78 9 3578 2357 2358 4 235 1
+        57 4 258 13578 6 12358 2359 2359 39
+        2 3 1 578 57 6 579 589 4
+        8 5 4 37 1 9 237 23 6
+        9 6 7 2 4 358 35 1 38
+    ");
+    let refinements = JournalWriter::new();
+
+    AlmostLockedSetXZ.analyze_grid(&index, &mut possible_values, &refinements);
+
+    let reason = RefinementReason::AlmostLockedSetXZ(
+        als(&[(4, 6)], &[2, 5]),
+        als(&[(5, 2), (5, 4), (5, 5), (5, 6)], &[2, 3, 5, 7, 8]),
+        [digit(2), digit(5)].into_iter().collect());
+
+    let expected: Vec<Refinement> = [(6, 6, 2), (6, 4, 5), (6, 6, 5), (5, 8, 3), (6, 4, 3), (6, 6, 3)].into_iter()
+        .map(|(row, column, removed)| Refinement::new(cell(row, column), digit(removed), reason.clone()))
+        .collect();
+
+    assert_eq!(expected, refinements.reader().get_events());
+}
+
+#[test]
+fn xy_wing() {
+    //  r9c7 and r8c4 + r8c7 + r8c8 are linked through r2c7, by 5 and 6 respectively, hence either holds 3.
+    let (mut possible_values, index) = indexed(GRID);
+    let refinements = JournalWriter::new();
+
+    AlmostLockedSetXYWing.analyze_grid(&index, &mut possible_values, &refinements);
+
+    let reason = RefinementReason::AlmostLockedSetXYWing(
+        als(&[(9, 7)], &[3, 5]),
+        als(&[(8, 4), (8, 7), (8, 8)], &[2, 3, 6, 7]),
+        als(&[(2, 7)], &[5, 6]));
+
+    assert_eq!(vec![Refinement::new(cell(8, 9), digit(3), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn death_blossom() {
+    //  r5c5 holds 1, 2 or 4, hence either r5c1, r1c5, or r6c1 + r6c4 is locked, and holds 3.
+    let (mut possible_values, index) = indexed("
+        38 9 9 9 23 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        13 9 9 9 124 9 9 9 9
+        37 9 9 47 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+    ");
+    let refinements = JournalWriter::new();
+
+    DeathBlossom.analyze_grid(&index, &mut possible_values, &refinements);
+
+    let petals = [
+        (digit(1), als(&[(5, 1)], &[1, 3])),
+        (digit(2), als(&[(1, 5)], &[2, 3])),
+        (digit(4), als(&[(6, 1), (6, 4)], &[3, 4, 7])),
+    ];
+
+    let reason = RefinementReason::DeathBlossom(cell(5, 5), petals.into_iter().collect());
+
+    assert_eq!(vec![Refinement::new(cell(1, 1), digit(3), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn chain() {
+    //  r1c1, r1c5, r5c5 and r5c2 are linked by 2, 3 and 4 in turn, hence either end holds 1.
+    let (mut possible_values, index) = indexed("
+        12 9 9 9 23 9 9 9 9
+        9 15 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 14 9 9 34 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+        9 9 9 9 9 9 9 9 9
+    ");
+    let refinements = JournalWriter::new();
+
+    AlmostLockedSetChain.analyze_grid(&index, &mut possible_values, &refinements);
+
+    let sets = [als(&[(1, 1)], &[1, 2]), als(&[(1, 5)], &[2, 3]), als(&[(5, 5)], &[3, 4]), als(&[(5, 2)], &[1, 4])];
+    let restricted = [digit(2), digit(3), digit(4)];
+
+    let reason = RefinementReason::AlmostLockedSetChain(sets.into_iter().collect(), restricted.into_iter().collect());
+
+    assert_eq!(vec![Refinement::new(cell(2, 2), digit(1), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn incremental_update() {
+    let (mut possible_values, mut index) = indexed(GRID);
+
+    //  A placement of 8 in r1c2, then a refinement removing 3 from r1c8.
+    possible_values.resolve(cell(1, 2), digit(8));
+
+    for peer in CellSet::peers(cell(1, 2)) {
+        possible_values.remove_possibility(peer, digit(8));
+    }
+
+    index.update(&possible_values);
+
+    assert_eq!(contents(&fresh(&possible_values)), contents(&index));
+
+    possible_values.remove_possibility(cell(1, 8), digit(3));
+
+    index.update(&possible_values);
+
+    assert_eq!(contents(&fresh(&possible_values)), contents(&index));
+}
+
+//  An entry of the index, and its links in order.
+type Contents = (AlmostLockedSet, [CellSet; DIMENSION], [CellSet; DIMENSION], Vec<(usize, DigitSet)>);
+
+fn contents(index: &AlmostLockedSetIndex) -> Vec<Contents> {
+    index.entries.iter()
+        .zip(&index.links)
+        .map(|(entry, links)| {
+            let mut links = links.clone();
+            links.sort_by_key(|(other, _)| *other);
+
+            (entry.set, entry.cells_of, entry.seen_by, links)
+        })
+        .collect()
+}
+
+fn fresh(possible_values: &PossibleValues) -> AlmostLockedSetIndex {
+    let mut index = AlmostLockedSetIndex::default();
+    index.update(possible_values);
+
+    index
+}
+
+fn indexed(candidates: &str) -> (PossibleValues, AlmostLockedSetIndex) {
+    let possible_values = parse_candidates(candidates);
+
+    let mut index = AlmostLockedSetIndex::default();
+    index.update(&possible_values);
+
+    (possible_values, index)
+}
+
+fn als(cells: &[(usize, usize)], digits: &[usize]) -> AlmostLockedSet {
+    AlmostLockedSet::new(
+        cells.iter().map(|(row, column)| cell(*row, *column)).collect(),
+        digits.iter().map(|value| digit(*value)).collect())
+}
+
+}
@@ -0,0 +1,136 @@
+//! Bivalue Universal Grave, relying on the uniqueness of the solution.
+//!
+//! Should every unsolved cell be reduced to 2 possible digits, with each possible digit appearing exactly twice in each
+//! group, the grid would have at least two solutions: a deadly pattern.
+
+use crate::model::{CellIndex, CellSet, Digit, DigitSet, Group, GroupIndex};
+use crate::solver::{JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, remove_possibility};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct BivalueUniversalGrave;
+
+impl AnalysisImpl for BivalueUniversalGrave {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        //  BUG+1: all unsolved cells are bivalue, except for a single trivalue cell.
+        let mut trivalue = None;
+
+        for cell in CellIndex::all() {
+            match possible_values.of_cell(cell).size() {
+                //  A cell without any possible digit is a contradiction, not a deadly pattern.
+                0 => return,
+                1..=2 => continue,
+                3 if trivalue.is_none() => trivalue = Some(cell),
+                _ => return,
+            }
+        }
+
+        let Some(cell) = trivalue else { return };
+
+        //  The digit appearing thrice in each group of the cell is the one breaking the deadly pattern, hence it must
+        //  be placed in the cell.
+        let thrice = |digit: &Digit| {
+            Group::groups(cell).into_iter().all(|group| possible_values.of_group(group).count(*digit) == 3)
+        };
+
+        let Some(digit) = possible_values.of_cell(cell).into_iter().find(thrice) else {
+            return;
+        };
+
+        //  Without the digit, the unsolved cells must form the deadly pattern: any digit appearing other than twice in
+        //  a group, among its unsolved cells, breaks it regardless of the trivalue cell.
+        let mut pattern = *possible_values;
+        pattern.remove_possibility(cell, digit);
+
+        let twice = |group: Group| {
+            let unsolved: Vec<DigitSet> = CellSet::from(group)
+                .into_iter()
+                .map(|cell| pattern.of_cell(cell))
+                .filter(|digits| digits.size() == 2)
+                .collect();
+
+            DigitSet::full().into_iter().all(|digit| {
+                let count = unsolved.iter().filter(|digits| digits.has(digit)).count();
+
+                count == 0 || count == 2
+            })
+        };
+
+        if !GroupIndex::all().map(Group::new).all(twice) {
+            return;
+        }
+
+        let reason = RefinementReason::BivalueUniversalGrave(digit);
+
+        for removed in possible_values.of_cell(cell) {
+            if removed != digit {
+                remove_possibility(possible_values, refinements, cell, removed, &reason);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+//  All unsolved cells are bivalue, except for r8c7, in which 3 appears thrice in each of its groups.
+const BUG_PLUS_ONE: &str = "
+    8 39 6 5 2 4 7 39 1
+    4 2 35 1 7 9 6 8 35
+    1 7 59 3 8 6 2 59 4
+    6 8 2 4 3 5 1 7 9
+    9 1 7 2 6 8 35 4 35
+    3 5 4 7 9 1 8 2 6
+    2 6 8 9 5 3 4 1 7
+    7 4 39 6 1 2 359 35 8
+    5 39 1 8 4 7 39 6 2
+";
+
+#[test]
+fn bug_plus_one() {
+    let mut possible_values = parse_candidates(BUG_PLUS_ONE);
+    let refinements = JournalWriter::new();
+
+    BivalueUniversalGrave.analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::BivalueUniversalGrave(digit(3));
+
+    assert_eq!(
+        vec![Refinement::new(cell(8, 7), digit(5), reason.clone()), Refinement::new(cell(8, 7), digit(9), reason)],
+        refinements.reader().get_events()
+    );
+    assert_eq!(DigitSet::from(digit(3)), possible_values.of_cell(cell(8, 7)));
+}
+
+#[test]
+fn bug_with_contradiction() {
+    let mut possible_values = parse_candidates(BUG_PLUS_ONE);
+    let refinements = JournalWriter::new();
+
+    possible_values.remove_possibility(cell(1, 1), digit(8));
+
+    BivalueUniversalGrave.analyze_grid(&mut possible_values, &refinements);
+
+    assert!(refinements.reader().is_empty());
+}
+
+#[test]
+fn bug_with_digit_thrice_elsewhere() {
+    //  r1c1 is bivalue, yet 3 then appears thrice in row 1 and in square 1, away from r8c7.
+    let mut possible_values = parse_candidates(&BUG_PLUS_ONE.replacen("8 39 6", "38 39 6", 1));
+    let refinements = JournalWriter::new();
+
+    BivalueUniversalGrave.analyze_grid(&mut possible_values, &refinements);
+
+    assert!(refinements.reader().is_empty());
+}
+
+}
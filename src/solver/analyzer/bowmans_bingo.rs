@@ -0,0 +1,159 @@
+//! Bowman's Bingo: trial and error, once logic runs out.
+//!
+//! A candidate of the most constrained cell is assumed, and singles are propagated from the assumption. Should no
+//! contradiction be reached, each candidate of the most constrained cell left is guessed in turn, recursively, up to a
+//! maximum depth. Should every guess fail, the assumption is false.
+
+use std::sync::Arc;
+
+use crate::model::{Candidate, CellIndex};
+use crate::solver::{DEFAULT_MAX_GUESS_DEPTH, Guess, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, remove_possibility};
+use super::forcing_chain::Propagation;
+
+#[derive(Clone, Debug)]
+pub(super) struct BowmansBingo {
+    max_depth: usize,
+}
+
+impl BowmansBingo {
+    pub(super) fn new(max_depth: usize) -> Self { Self { max_depth } }
+}
+
+impl Default for BowmansBingo {
+    fn default() -> Self { Self::new(DEFAULT_MAX_GUESS_DEPTH) }
+}
+
+impl AnalysisImpl for BowmansBingo {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        //  The most constrained cell always holds a false candidate, unless the grid has multiple solutions, in which
+        //  case other cells may still.
+        let mut cells: Vec<CellIndex> = CellIndex::all().filter(|cell| possible_values.of_cell(*cell).size() > 1).collect();
+
+        cells.sort_by_key(|cell| possible_values.of_cell(*cell).size());
+
+        for cell in cells {
+            for digit in possible_values.of_cell(cell) {
+                let Some(guess) = guess(possible_values, Candidate::new(cell, digit), self.max_depth) else { continue };
+
+                let reason = RefinementReason::BowmansBingo(Arc::new(guess));
+
+                if remove_possibility(possible_values, refinements, cell, digit, &reason) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  Guesses the assumption, and recursively the candidates of the most constrained cell left, up to the depth.
+//
+//  Returns the trace of the guess, if it failed, or None if it led to a solution, or if the depth was exhausted.
+fn guess(possible_values: &PossibleValues, assumption: Candidate, depth: usize) -> Option<Guess> {
+    if depth == 0 {
+        return None;
+    }
+
+    let propagation = Propagation::new(possible_values, assumption);
+
+    if propagation.is_contradiction() {
+        return Some(Guess::new(propagation.branch(), Vec::new()));
+    }
+
+    let possible_values = propagation.possible_values();
+
+    let cell = CellIndex::all()
+        .filter(|cell| possible_values.of_cell(*cell).size() > 1)
+        .min_by_key(|cell| possible_values.of_cell(*cell).size());
+
+    //  Solved, without contradiction.
+    let cell = cell?;
+
+    let nested = possible_values.of_cell(cell)
+        .into_iter()
+        .map(|digit| guess(possible_values, Candidate::new(cell, digit), depth - 1))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Guess::new(propagation.branch(), nested))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::solver::ForcingContradiction;
+use crate::test_utils::{cell, column, digit, parse_candidates};
+
+const STALLED: &str = "
+    3 6 5 7 4 9 2 1 8
+    48 48 9 1 2 5 7 3 6
+    7 1 2 68 3 68 9 4 5
+    1 5 68 24689 689 2468 68 7 3
+    9 7 4 5 68 3 68 2 1
+    2 3 68 68 7 1 5 9 4
+    468 248 7 23468 1 2468 34 5 9
+    46 9 3 46 5 7 1 8 2
+    5 248 1 23489 89 248 34 6 7
+";
+
+#[test]
+fn single_guess() {
+    //  Assuming 8 in r3c4 leaves no room for 6 in c5.
+    let guess = analyze(1, Candidate::new(cell(3, 4), digit(8)));
+
+    assert_eq!(Candidate::new(cell(3, 4), digit(8)), guess.branch().assumption());
+    assert_eq!(Some(ForcingContradiction::MissingDigit(digit(6), column(5))), guess.branch().contradiction());
+    assert!(guess.nested().is_empty());
+    assert_eq!(1, guess.depth());
+}
+
+#[test]
+fn nested_guesses() {
+    //  Assuming 8 in r2c1 leads to no contradiction by itself, but whichever of 6 or 8 is then guessed in r3c4 does.
+    let guess = analyze(2, Candidate::new(cell(2, 1), digit(8)));
+
+    assert_eq!(Candidate::new(cell(2, 1), digit(8)), guess.branch().assumption());
+    assert_eq!(None, guess.branch().contradiction());
+    assert_eq!(2, guess.depth());
+
+    let nested: Vec<_> = guess.nested().iter()
+        .map(|nested| (nested.branch().assumption(), nested.branch().contradiction(), nested.depth()))
+        .collect();
+
+    let expected = vec![
+        (Candidate::new(cell(3, 4), digit(6)), Some(ForcingContradiction::MissingDigit(digit(4), column(1))), 1),
+        (Candidate::new(cell(3, 4), digit(8)), Some(ForcingContradiction::MissingDigit(digit(6), column(5))), 1),
+    ];
+
+    assert_eq!(expected, nested);
+}
+
+//  Analyzes the fixture with guesses up to the depth, expecting the single removal of the candidate.
+fn analyze(max_depth: usize, removed: Candidate) -> Guess {
+    let mut possible_values = parse_candidates(STALLED);
+    let refinements = JournalWriter::new();
+
+    BowmansBingo::new(max_depth).analyze_grid(&mut possible_values, &refinements);
+
+    let refinements = refinements.reader().get_events();
+
+    assert_eq!(1, refinements.len());
+    assert_eq!(removed, Candidate::new(refinements[0].cell(), refinements[0].removed()));
+
+    let RefinementReason::BowmansBingo(guess) = refinements[0].reason() else {
+        panic!("Unexpected reason {:?}", refinements[0].reason())
+    };
+
+    Guess::clone(guess)
+}
+
+}
@@ -0,0 +1,97 @@
+//! The graph of candidates, linked by the rules of sudoku.
+//!
+//! Two candidates are strongly linked when at least one of them must be true, as is the case for the 2 candidates of
+//! a bivalue cell, or the 2 candidates of a digit only possible twice within a group. Two candidates are weakly linked
+//! when at most one of them may be true, as is the case for 2 candidates of a single cell, or 2 candidates of the same
+//! digit within a group.
+
+use crate::model::{DIMENSION, Candidate, CandidateSet, CellIndex, CellSet, DigitSet, Group, GroupIndex};
+use crate::solver::PossibleValues;
+
+//  The graph of the candidates of the unsolved cells.
+#[derive(Clone, Debug)]
+pub(super) struct CandidateGraph {
+    candidates: CandidateSet,
+    //  Strong links, indexed by candidate.
+    strong_links: Vec<Vec<Candidate>>,
+    //  Peers of each cell.
+    peers: Vec<CellSet>,
+}
+
+impl CandidateGraph {
+    pub(super) fn new(possible_values: &PossibleValues) -> Self {
+        let mut candidates = CandidateSet::empty();
+        let mut strong_links = vec![Vec::new(); NUMBER_CANDIDATES];
+
+        for cell in CellIndex::all() {
+            let digits = possible_values.of_cell(cell);
+
+            if digits.size() < 2 {
+                continue;
+            }
+
+            for digit in digits {
+                candidates.add(Candidate::new(cell, digit));
+            }
+
+            //  Bivalue cell.
+            if digits.size() == 2 {
+                let mut digits = digits.into_iter();
+                let (first, second) = (digits.next().expect("2 digits"), digits.next().expect("2 digits"));
+
+                Self::link(&mut strong_links, Candidate::new(cell, first), Candidate::new(cell, second));
+            }
+        }
+
+        //  Bilocation digit.
+        for group in GroupIndex::all().map(Group::new) {
+            for digit in DigitSet::full() {
+                let mut cells = group.cells().into_iter().filter(|cell| possible_values.of_cell(*cell).has(digit));
+
+                let (Some(first), Some(second), None) = (cells.next(), cells.next(), cells.next()) else { continue };
+
+                let (first, second) = (Candidate::new(first, digit), Candidate::new(second, digit));
+
+                if candidates.has(first) && candidates.has(second) {
+                    Self::link(&mut strong_links, first, second);
+                }
+            }
+        }
+
+        let peers = CellIndex::all().map(CellSet::peers).collect();
+
+        Self { candidates, strong_links, peers }
+    }
+
+    //  Returns all the candidates of the unsolved cells.
+    pub(super) fn candidates(&self) -> CandidateSet { self.candidates }
+
+    //  Returns the candidates strongly linked to the specified candidate.
+    pub(super) fn strong_links(&self, candidate: Candidate) -> &[Candidate] { &self.strong_links[Self::index(candidate)] }
+
+    //  Returns the peers of the specified cell.
+    pub(super) fn peers(&self, cell: CellIndex) -> CellSet { self.peers[cell.value()] }
+
+    //  Returns the cells seeing any of the specified cells.
+    pub(super) fn seen_by_any(&self, cells: CellSet) -> CellSet {
+        cells.into_iter().fold(CellSet::empty(), |seen, cell| seen.union(&self.peers(cell)))
+    }
+
+    fn index(candidate: Candidate) -> usize { candidate.cell().value() * DIMENSION + candidate.digit().value() - 1 }
+
+    fn link(strong_links: &mut [Vec<Candidate>], first: Candidate, second: Candidate) {
+        for (from, to) in [(first, second), (second, first)] {
+            let links = &mut strong_links[Self::index(from)];
+
+            if !links.contains(&to) {
+                links.push(to);
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+const NUMBER_CANDIDATES: usize = DIMENSION * DIMENSION * DIMENSION;
@@ -0,0 +1,403 @@
+//! Chains: X-Cycles and Alternating Inference Chains, with grouped nodes.
+//!
+//! A chain alternates strong links, at least one end of which is true, and weak links, at most one end of which is
+//! true. Starting and ending with a strong link, either its first or last node is true, hence any candidate weakly
+//! linked to both is false. Should the last node be weakly linked to the first, the chain is a continuous loop, in
+//! which every weak link is also strong, and any candidate weakly linked to both ends of a weak link is false.
+//!
+//! A node is either a single candidate, or the candidates of a digit within the overlap of a square and a line.
+
+use std::sync::Arc;
+
+use crate::model::{Candidate, CandidateSet, CellIndex, CellSet, Digit, DigitSet, Group, GroupIndex, SquareIndex};
+use crate::solver::{DEFAULT_MAX_CHAIN_LENGTH, ChainKind, ChainNode, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, remove_possibility};
+
+#[derive(Clone, Debug)]
+pub(super) struct XCycle {
+    max_length: usize,
+}
+
+impl XCycle {
+    pub(super) fn new(max_length: usize) -> Self { Self { max_length } }
+}
+
+impl Default for XCycle {
+    fn default() -> Self { Self::new(DEFAULT_MAX_CHAIN_LENGTH) }
+}
+
+impl AnalysisImpl for XCycle {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for digit in DigitSet::full() {
+            let graph = ChainGraph::new(possible_values, &[digit]);
+
+            if graph.analyze(possible_values, refinements, self.max_length, RefinementReason::XCycle) {
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct AlternatingInferenceChain {
+    max_length: usize,
+}
+
+impl AlternatingInferenceChain {
+    pub(super) fn new(max_length: usize) -> Self { Self { max_length } }
+}
+
+impl Default for AlternatingInferenceChain {
+    fn default() -> Self { Self::new(DEFAULT_MAX_CHAIN_LENGTH) }
+}
+
+impl AnalysisImpl for AlternatingInferenceChain {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        let digits: Vec<Digit> = DigitSet::full().into_iter().collect();
+        let graph = ChainGraph::new(possible_values, &digits);
+
+        graph.analyze(possible_values, refinements, self.max_length, RefinementReason::AlternatingInferenceChain);
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The graph of the nodes, for the specified digits, and their links.
+struct ChainGraph {
+    nodes: Vec<ChainNode>,
+    strong_links: Vec<Vec<usize>>,
+    weak_links: Vec<Vec<usize>>,
+    //  For each node, the candidates weakly linked to it.
+    linked: Vec<CandidateSet>,
+}
+
+impl ChainGraph {
+    fn new(possible_values: &PossibleValues, digits: &[Digit]) -> Self {
+        let peers: Vec<CellSet> = CellIndex::all().map(CellSet::peers).collect();
+        let seen_by = |cells: CellSet| cells.into_iter().fold(CellSet::full(), |seen, cell| seen.intersection(&peers[cell.value()]));
+
+        let multi_digit = digits.len() > 1;
+
+        let mut nodes = Vec::new();
+
+        for &digit in digits {
+            let candidates = candidate_cells(possible_values, digit);
+
+            nodes.extend(candidates.into_iter().map(|cell| ChainNode::new(digit, CellSet::from(cell))));
+
+            for square in SquareIndex::all() {
+                let square_cells = CellSet::from(Group::Square(square));
+
+                for line in square.rows().map(Group::Row).chain(square.columns().map(Group::Column)) {
+                    let cells = candidates.intersection(&square_cells).intersection(&CellSet::from(line));
+
+                    if cells.size() > 1 {
+                        nodes.push(ChainNode::new(digit, cells));
+                    }
+                }
+            }
+        }
+
+        let mut strong_links = vec![Vec::new(); nodes.len()];
+        let mut weak_links = vec![Vec::new(); nodes.len()];
+        let mut linked = vec![CandidateSet::empty(); nodes.len()];
+
+        for (index, node) in nodes.iter().enumerate() {
+            let seen = seen_by(node.cells());
+            let candidates = candidate_cells(possible_values, node.digit());
+
+            linked[index] = seen.intersection(&candidates).into_iter().map(|cell| Candidate::new(cell, node.digit())).collect();
+
+            if multi_digit && !node.is_grouped() {
+                let cell = node.cells().into_iter().next().expect("Single cell");
+
+                for digit in possible_values.of_cell(cell) {
+                    if digit != node.digit() {
+                        linked[index].add(Candidate::new(cell, digit));
+                    }
+                }
+            }
+
+            for (other_index, other) in nodes.iter().enumerate() {
+                if other_index == index {
+                    continue;
+                }
+
+                let same_digit = other.digit() == node.digit() && other.cells().is_subset_of(&seen);
+                let same_cell = !node.is_grouped() && other.cells() == node.cells();
+
+                if same_digit || same_cell {
+                    weak_links[index].push(other_index);
+                }
+            }
+        }
+
+        //  Strong links within a group: the candidates of the digit within the group are split between 2 nodes.
+        for group in GroupIndex::all().map(Group::new) {
+            let group_cells = CellSet::from(group);
+
+            for &digit in digits {
+                let candidates = candidate_cells(possible_values, digit).intersection(&group_cells);
+
+                let within: Vec<usize> = (0..nodes.len())
+                    .filter(|index| nodes[*index].digit() == digit && nodes[*index].cells().is_subset_of(&group_cells))
+                    .collect();
+
+                for (position, &first) in within.iter().enumerate() {
+                    for &second in &within[position + 1..] {
+                        let (a, b) = (nodes[first].cells(), nodes[second].cells());
+
+                        if a.intersection(&b).is_empty() && a.union(&b) == candidates {
+                            link(&mut strong_links, first, second);
+                        }
+                    }
+                }
+            }
+        }
+
+        //  Strong links within a bivalue cell.
+        if multi_digit {
+            for cell in CellIndex::all() {
+                if possible_values.of_cell(cell).size() != 2 {
+                    continue;
+                }
+
+                let pair: Vec<usize> = (0..nodes.len()).filter(|index| nodes[*index].cells() == CellSet::from(cell)).collect();
+
+                if let [first, second] = pair[..] {
+                    link(&mut strong_links, first, second);
+                }
+            }
+        }
+
+        Self { nodes, strong_links, weak_links, linked }
+    }
+
+    //  Searches for the shortest productive chain, of at most `max_length` nodes, and applies its eliminations.
+    //
+    //  Returns whether any possibility was removed.
+    fn analyze<F>(
+        &self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        max_length: usize,
+        reason: F,
+    )
+        -> bool
+    where
+        F: Fn(ChainKind, Arc<[ChainNode]>) -> RefinementReason,
+    {
+        let mut best: Option<(Vec<usize>, ChainKind, CandidateSet)> = None;
+
+        for start in 0..self.nodes.len() {
+            if self.strong_links[start].is_empty() {
+                continue;
+            }
+
+            let limit = best.as_ref().map_or(max_length, |(path, _, _)| path.len() - 1);
+
+            if let Some(found) = self.search(start, limit) {
+                best = Some(found);
+            }
+        }
+
+        let Some((path, kind, targets)) = best else { return false };
+
+        let nodes: Arc<[ChainNode]> = path.iter().map(|index| self.nodes[*index]).collect();
+        let reason = reason(kind, nodes);
+
+        let mut removed = false;
+
+        for candidate in targets {
+            removed |= remove_possibility(possible_values, refinements, candidate.cell(), candidate.digit(), &reason);
+        }
+
+        removed
+    }
+
+    //  Searches, breadth-first, for the shortest productive chain from the start, of at most `limit` nodes.
+    fn search(&self, start: usize, limit: usize) -> Option<(Vec<usize>, ChainKind, CandidateSet)> {
+        //  States are (node, parity): parity 0 when next link is strong, 1 when next link is weak.
+        let state = |node: usize, parity: usize| node * 2 + parity;
+
+        let mut parents = vec![None; self.nodes.len() * 2];
+        let mut visited = vec![false; self.nodes.len() * 2];
+        let mut frontier = vec![state(start, 0)];
+
+        visited[state(start, 0)] = true;
+
+        for length in 2..=limit {
+            let mut next = Vec::new();
+
+            for current in frontier {
+                let (node, parity) = (current / 2, current % 2);
+                let links = if parity == 0 { &self.strong_links[node] } else { &self.weak_links[node] };
+
+                for &other in links {
+                    let reached = state(other, 1 - parity);
+
+                    if visited[reached] {
+                        continue;
+                    }
+
+                    visited[reached] = true;
+                    parents[reached] = Some(current);
+                    next.push(reached);
+
+                    //  Only chains ending with a strong link are conclusive.
+                    if parity == 1 {
+                        continue;
+                    }
+
+                    let path = || {
+                        let mut path = vec![other];
+                        let mut current = parents[reached];
+
+                        while let Some(state) = current {
+                            path.push(state / 2);
+                            current = parents[state];
+                        }
+
+                        path.reverse();
+                        path
+                    };
+
+                    let targets = self.linked[start].intersection(&self.linked[other]);
+
+                    if !targets.is_empty() {
+                        return Some((path(), ChainKind::Open, targets));
+                    }
+
+                    if length > 2 && self.weak_links[other].contains(&start) {
+                        let path = path();
+                        let targets = self.loop_targets(&path);
+
+                        if !targets.is_empty() {
+                            return Some((path, ChainKind::ContinuousLoop, targets));
+                        }
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        None
+    }
+
+    //  Returns the candidates weakly linked to both ends of any weak link of the loop, including the closing link.
+    fn loop_targets(&self, path: &[usize]) -> CandidateSet {
+        let mut targets = CandidateSet::empty();
+
+        for position in (1..path.len()).step_by(2) {
+            let (first, second) = (path[position], path[(position + 1) % path.len()]);
+
+            targets = targets.union(&self.linked[first].intersection(&self.linked[second]));
+        }
+
+        targets
+    }
+}
+
+fn link(links: &mut [Vec<usize>], first: usize, second: usize) {
+    links[first].push(second);
+    links[second].push(first);
+}
+
+fn candidate_cells(possible_values: &PossibleValues, digit: Digit) -> CellSet {
+    CellIndex::all()
+        .filter(|cell| possible_values.of_cell(*cell).size() > 1 && possible_values.of_cell(*cell).has(digit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+//  1 is only possible in r1c1 and r1c7 within r1, in r1c7 and the group r2c8-r3c8 within s3, and in the group and r7c8
+//  within c8, while r4c1 and r7c5 break any other strong link: either r1c1 or r7c8 holds 1, and r7c1 sees both.
+//
+//  Every other digit is only possible in a single cell, so that the grouped link is the only way to r7c1.
+const GROUPED: &str = "
+    12 9 9 9 9 9 13 9 9
+    9 9 9 9 9 9 9 14 9
+    9 9 9 9 9 9 9 15 9
+    18 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    17 9 9 9 19 9 9 16 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+#[test]
+fn grouped_x_cycle() {
+    let mut possible_values = parse_candidates(GROUPED);
+    let refinements = JournalWriter::new();
+
+    let graph = ungrouped(ChainGraph::new(&possible_values, &[digit(1)]));
+
+    let reason = RefinementReason::XCycle;
+
+    assert!(!graph.analyze(&mut possible_values.clone(), &refinements, DEFAULT_MAX_CHAIN_LENGTH, reason));
+
+    XCycle::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::XCycle(ChainKind::Open, grouped_chain());
+
+    assert_eq!(vec![Refinement::new(cell(7, 1), digit(1), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn grouped_alternating_inference_chain() {
+    let mut possible_values = parse_candidates(GROUPED);
+    let refinements = JournalWriter::new();
+
+    let digits: Vec<Digit> = DigitSet::full().into_iter().collect();
+    let graph = ungrouped(ChainGraph::new(&possible_values, &digits));
+    let reason = RefinementReason::AlternatingInferenceChain;
+
+    assert!(!graph.analyze(&mut possible_values.clone(), &refinements, DEFAULT_MAX_CHAIN_LENGTH, reason));
+
+    AlternatingInferenceChain::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::AlternatingInferenceChain(ChainKind::Open, grouped_chain());
+
+    assert_eq!(vec![Refinement::new(cell(7, 1), digit(1), reason)], refinements.reader().get_events());
+}
+
+//  Returns the chain of the fixture: r1c1 = r1c7 - r2c8/r3c8 = r7c8.
+fn grouped_chain() -> Arc<[ChainNode]> {
+    [vec![cell(1, 1)], vec![cell(1, 7)], vec![cell(2, 8), cell(3, 8)], vec![cell(7, 8)]].into_iter()
+        .map(|cells| ChainNode::new(digit(1), cells.into_iter().collect()))
+        .collect()
+}
+
+//  Returns the graph, without any link to or from its grouped nodes.
+fn ungrouped(mut graph: ChainGraph) -> ChainGraph {
+    let grouped: Vec<bool> = graph.nodes.iter().map(ChainNode::is_grouped).collect();
+
+    for links in [&mut graph.strong_links, &mut graph.weak_links] {
+        for (index, links) in links.iter_mut().enumerate() {
+            links.retain(|other| !grouped[index] && !grouped[*other]);
+        }
+    }
+
+    graph
+}
+
+}
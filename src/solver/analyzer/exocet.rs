@@ -0,0 +1,276 @@
+//! Junior Exocet: a pair of base cells, whose digits must reappear in a pair of target cells.
+//!
+//! For rows, the base is formed by 2 cells of a square within a single row, and the targets by 1 cell in each of the
+//! other 2 squares of the band, within the other 2 rows. The cross-lines are the columns of the targets, and the column
+//! of the square of the base which contains no base cell.
+//!
+//! Each digit of the base appears once in each cross-line, in different rows. Should its positions within the
+//! cross-lines outside the band be covered by 2 rows, it appears at least once within the band in a cross-line, where
+//! it can only be in a target, provided the cells of the targets' columns in the other target's row cannot hold any
+//! base digit. Hence the 2 digits of the base are the 2 digits of the targets.
+//!
+//! The cells of the targets' cross-lines in the other target's line are the companion cells of the standard Junior
+//! Exocet, and the covering lines its cover houses.
+//!
+//! This is a partial implementation of the standard pattern: only lines parallel to the base are considered as cover
+//! houses, never the cross-lines themselves, and only the 2 eliminations above are made, within the targets and the
+//! base. The other standard eliminations, such as those within the cross-lines and the cover houses, or those of the
+//! mirror nodes, are not.
+
+use crate::model::{DIMENSION, SQUARE_DIMENSION, CellIndex, ColumnIndex, DigitSet, Group, GroupSet, RowIndex};
+use crate::solver::{JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, cell_at, remove_possibility};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct Exocet;
+
+impl AnalysisImpl for Exocet {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for transposed in [false, true] {
+            let frame = Frame { transposed };
+
+            for pattern in frame.patterns() {
+                if pattern.analyze(possible_values, refinements) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The frame of reference of the pattern: rows are lines, and columns are cross-lines, unless transposed.
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    transposed: bool,
+}
+
+impl Frame {
+    fn cell(self, line: usize, position: usize) -> CellIndex {
+        let (row, column) = if self.transposed { (position, line) } else { (line, position) };
+
+        cell_at(row, column)
+    }
+
+    fn cross_line(self, position: usize) -> Group {
+        if self.transposed {
+            Group::Row(RowIndex::new(position).expect("Valid row"))
+        } else {
+            Group::Column(ColumnIndex::new(position).expect("Valid column"))
+        }
+    }
+
+    //  Enumerates all the candidate patterns: base and targets, regardless of possible values.
+    fn patterns(self) -> impl Iterator<Item = Pattern> {
+        let mut result = Vec::new();
+
+        for band in 0..SQUARE_DIMENSION {
+            let lines: Vec<usize> = (0..SQUARE_DIMENSION).map(|offset| band * SQUARE_DIMENSION + offset).collect();
+
+            for base_stack in 0..SQUARE_DIMENSION {
+                let positions: Vec<usize> = (0..SQUARE_DIMENSION).map(|offset| base_stack * SQUARE_DIMENSION + offset).collect();
+                let other_stacks: Vec<usize> = (0..SQUARE_DIMENSION).filter(|stack| *stack != base_stack).collect();
+
+                for &base_line in &lines {
+                    let target_lines: Vec<usize> = lines.iter().copied().filter(|line| *line != base_line).collect();
+
+                    for &free in &positions {
+                        let base_positions: Vec<usize> = positions.iter().copied().filter(|position| *position != free).collect();
+
+                        for (first_line, second_line) in [(target_lines[0], target_lines[1]), (target_lines[1], target_lines[0])] {
+                            for first in 0..SQUARE_DIMENSION {
+                                for second in 0..SQUARE_DIMENSION {
+                                    let first = other_stacks[0] * SQUARE_DIMENSION + first;
+                                    let second = other_stacks[1] * SQUARE_DIMENSION + second;
+
+                                    result.push(Pattern {
+                                        frame: self,
+                                        band,
+                                        base: [self.cell(base_line, base_positions[0]), self.cell(base_line, base_positions[1])],
+                                        targets: [self.cell(first_line, first), self.cell(second_line, second)],
+                                        companions: [self.cell(second_line, first), self.cell(first_line, second)],
+                                        cross_lines: [free, first, second],
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        result.into_iter()
+    }
+}
+
+struct Pattern {
+    frame: Frame,
+    band: usize,
+    base: [CellIndex; 2],
+    targets: [CellIndex; 2],
+    //  The cells of the cross-line of each target, in the line of the other target.
+    companions: [CellIndex; 2],
+    //  The positions of the cross-lines.
+    cross_lines: [usize; 3],
+}
+
+impl Pattern {
+    //  Returns whether any possibility was removed.
+    fn analyze(&self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        if self.base.iter().chain(&self.targets).any(|cell| possible_values.of_cell(*cell).size() < 2) {
+            return false;
+        }
+
+        let digits = possible_values.of_cell(self.base[0]).union(&possible_values.of_cell(self.base[1]));
+
+        if !(3..=4).contains(&digits.size()) {
+            return false;
+        }
+
+        if self.targets.iter().any(|cell| possible_values.of_cell(*cell).intersection(&digits).is_empty()) {
+            return false;
+        }
+
+        if self.companions.iter().any(|cell| !possible_values.of_cell(*cell).intersection(&digits).is_empty()) {
+            return false;
+        }
+
+        if !self.is_covered(possible_values, digits) {
+            return false;
+        }
+
+        let reason = RefinementReason::Exocet(
+            self.base.into_iter().collect(),
+            self.targets.into_iter().collect(),
+            self.cross_lines.into_iter().map(|position| self.frame.cross_line(position)).collect::<GroupSet>(),
+        );
+
+        let mut removed = false;
+
+        //  The targets only hold base digits.
+        for target in self.targets {
+            for digit in possible_values.of_cell(target).difference(&digits) {
+                removed |= remove_possibility(possible_values, refinements, target, digit, &reason);
+            }
+        }
+
+        //  The base only holds digits which the targets may hold.
+        let target_digits = possible_values.of_cell(self.targets[0]).union(&possible_values.of_cell(self.targets[1]));
+
+        for base in self.base {
+            for digit in digits.difference(&target_digits) {
+                removed |= remove_possibility(possible_values, refinements, base, digit, &reason);
+            }
+        }
+
+        removed
+    }
+
+    //  Returns whether, for each base digit, its positions within the cross-lines outside the band are covered by at
+    //  most 2 lines.
+    fn is_covered(&self, possible_values: &PossibleValues, digits: DigitSet) -> bool {
+        let outside: Vec<usize> = (0..DIMENSION).filter(|line| line / SQUARE_DIMENSION != self.band).collect();
+
+        digits.into_iter().all(|digit| {
+            let lines = outside.iter()
+                .filter(|line| {
+                    self.cross_lines.iter().any(|position| possible_values.of_cell(self.frame.cell(**line, *position)).has(digit))
+                })
+                .count();
+
+            lines <= 2
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::model::CellSet;
+use crate::test_utils::{cell, digit, parse_candidates, row};
+
+//  The base r1c1 and r3c1 holds 1, 2, 5 and 9, and the targets are r4c3 and r7c2, with the companions r4c2 and r7c3
+//  holding none of them; outside the stack, each base digit is covered by 2 columns within r2, r4 and r7.
+fn candidates(r4c3: &str, r7c2: &str) -> String {
+    format!("
+        1259 125679 3 12457 12459 145679 24789 24589 2479
+        4 12579 12579 1257 8 1579 279 3 6
+        259 25679 8 23457 23459 345679 1 2459 2479
+        12589 4 {r4c3} 158 6 158 289 7 3
+        12358 12357 12567 9 1345 134578 2468 1248 124
+        1389 1379 1679 13478 134 2 4689 1489 5
+        12359 {r7c2} 4 1235 7 1359 239 6 8
+        6 123589 1259 123458 123459 134589 23479 1249 12479
+        7 12389 129 6 12349 13489 5 1249 1249
+    ")
+}
+
+#[test]
+fn junior_exocet() {
+    let reason = RefinementReason::Exocet(
+        [cell(1, 1), cell(3, 1)].into_iter().collect::<CellSet>(),
+        [cell(4, 3), cell(7, 2)].into_iter().collect::<CellSet>(),
+        [row(2), row(4), row(7)].into_iter().collect(),
+    );
+
+    //  The targets only hold base digits.
+    let mut possible_values = parse_candidates(&candidates("1259", "12359"));
+    let refinements = JournalWriter::new();
+
+    Exocet.analyze_grid(&mut possible_values, &refinements);
+
+    assert_eq!(vec![Refinement::new(cell(7, 2), digit(3), reason.clone())], refinements.reader().get_events());
+
+    //  The base only holds digits which the targets may hold.
+    let mut possible_values = parse_candidates(&candidates("125", "1235"));
+    let refinements = JournalWriter::new();
+
+    Exocet.analyze_grid(&mut possible_values, &refinements);
+
+    assert_eq!(
+        vec![
+            Refinement::new(cell(7, 2), digit(3), reason.clone()),
+            Refinement::new(cell(1, 1), digit(9), reason.clone()),
+            Refinement::new(cell(3, 1), digit(9), reason),
+        ],
+        refinements.reader().get_events()
+    );
+}
+
+#[test]
+fn cross_line_cover() {
+    //  Within r2, r4 and r7, 2 is only possible in r2c4, r2c6, r4c7 and r7c7: the standard pattern covers them with r2
+    //  and c7, but the cross-lines are not cover houses here, and 3 columns are needed.
+    let candidates = candidates("1259", "12359")
+        .replacen("8 1579 279", "8 12579 79", 1)
+        .replacen("4 1235 7", "4 135 7", 1);
+
+    let mut possible_values = parse_candidates(&candidates);
+    let refinements = JournalWriter::new();
+
+    Exocet.analyze_grid(&mut possible_values, &refinements);
+
+    assert!(refinements.reader().is_empty());
+}
+
+#[test]
+fn uncovered_base_digit() {
+    //  Within r2, r4 and r7, 9 is possible in r2c6, r2c7, r4c4, r4c7, r7c6 and r7c7, which no 2 lines cover.
+    let mut possible_values = parse_candidates(&candidates("1259", "12359").replacen("4 1259 158", "4 1259 1589", 1));
+    let refinements = JournalWriter::new();
+
+    Exocet.analyze_grid(&mut possible_values, &refinements);
+
+    assert!(refinements.reader().is_empty());
+}
+
+}
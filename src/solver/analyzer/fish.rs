@@ -0,0 +1,473 @@
+//! Fish: X-Wing, Swordfish, and Jellyfish, with their finned and sashimi variants, and Franken and Mutant fishes.
+//!
+//! A fish of size N is formed, for a given digit, by N base lines (rows, or columns) in which the digit is only
+//! possible within N cover lines (columns, or rows). Since the digit must appear once in each base line, it appears N
+//! times within the cover lines, hence exactly once in each, and cannot appear anywhere else within the cover lines.
+//!
+//! Franken and Mutant fishes generalize the base and cover to any groups, squares included, as long as the base groups
+//! do not overlap.
+
+use crate::model::{DIMENSION, SQUARE_DIMENSION, CellIndex, CellSet, ColumnIndex, Digit, DigitSet, Group, GroupIndex, GroupSet, RowIndex, SquareIndex};
+use crate::solver::{DEFAULT_MAX_FISH_SIZE, DEFAULT_MAX_FRANKEN_FISH_FINS, DEFAULT_MAX_FRANKEN_FISH_SIZE, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, cell_at, combinations, remove_possibilities, remove_possibility, select};
+
+#[derive(Clone, Debug)]
+pub(super) struct Fish {
+    max_size: usize,
+}
+
+impl Fish {
+    pub(super) fn new(max_size: usize) -> Self { Self { max_size } }
+}
+
+impl Default for Fish {
+    fn default() -> Self { Self::new(DEFAULT_MAX_FISH_SIZE) }
+}
+
+impl AnalysisImpl for Fish {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for size in 2..=self.max_size {
+            for digit in DigitSet::full() {
+                for orientation in [Orientation::Rows, Orientation::Columns] {
+                    let positions = orientation.positions(possible_values, digit);
+
+                    for base in base_lines(&positions, size) {
+                        let cover = base.iter().fold(0, |cover, line| cover | positions[*line]);
+
+                        if cover.count_ones() as usize != size {
+                            continue;
+                        }
+
+                        let fish = FishPattern { orientation, digit, base: &base, cover, fins: CellSet::empty() };
+
+                        if fish.eliminate(possible_values, refinements, CellSet::full()) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct FinnedFish {
+    max_size: usize,
+}
+
+impl FinnedFish {
+    pub(super) fn new(max_size: usize) -> Self { Self { max_size } }
+}
+
+impl Default for FinnedFish {
+    fn default() -> Self { Self::new(DEFAULT_MAX_FISH_SIZE) }
+}
+
+impl AnalysisImpl for FinnedFish {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for size in 2..=self.max_size {
+            for digit in DigitSet::full() {
+                for orientation in [Orientation::Rows, Orientation::Columns] {
+                    let positions = orientation.positions(possible_values, digit);
+
+                    for base in base_lines(&positions, size) {
+                        let all = base.iter().fold(0, |all, line| all | positions[*line]);
+
+                        //  Fins are confined to a single square, hence cover at most SQUARE_DIMENSION extra positions.
+                        let extra = (all.count_ones() as usize).saturating_sub(size);
+
+                        if extra == 0 || extra > SQUARE_DIMENSION {
+                            continue;
+                        }
+
+                        let all_positions: Vec<usize> = (0..DIMENSION).filter(|position| all & (1 << position) != 0).collect();
+
+                        for mask in combinations(all_positions.len(), size) {
+                            let cover = select(&all_positions, mask).fold(0, |cover, position| cover | (1 << position));
+
+                            let fins: CellSet = base.iter()
+                                .flat_map(|line| {
+                                    let fins = positions[*line] & !cover;
+
+                                    (0..DIMENSION)
+                                        .filter(move |position| fins & (1 << position) != 0)
+                                        .map(move |position| orientation.cell(*line, position))
+                                })
+                                .collect();
+
+                            //  Either the fins are all false, and the fish holds, or one of the fins is true, hence
+                            //  only cells seeing all fins can be eliminated: those in the square of the fins.
+                            let Some(square) = single_square(fins) else { continue };
+
+                            let targets: CellSet = Group::Square(square).cells().into_iter().collect();
+
+                            let fish = FishPattern { orientation, digit, base: &base, cover, fins };
+
+                            if fish.eliminate(possible_values, refinements, targets) {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct FrankenFish {
+    max_size: usize,
+    max_fins: usize,
+}
+
+impl FrankenFish {
+    pub(super) fn new(max_size: usize, max_fins: usize) -> Self { Self { max_size, max_fins } }
+}
+
+impl Default for FrankenFish {
+    fn default() -> Self { Self::new(DEFAULT_MAX_FRANKEN_FISH_SIZE, DEFAULT_MAX_FRANKEN_FISH_FINS) }
+}
+
+impl AnalysisImpl for FrankenFish {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for size in 2..=self.max_size {
+            for digit in DigitSet::full() {
+                let candidates: CellSet = CellIndex::all()
+                    .filter(|cell| possible_values.of_cell(*cell).size() > 1 && possible_values.of_cell(*cell).has(digit))
+                    .collect();
+
+                //  The groups, with the cells in which the digit is possible.
+                let groups: Vec<(Group, CellSet)> = GroupIndex::all()
+                    .map(Group::new)
+                    .map(|group| (group, CellSet::from(group).intersection(&candidates)))
+                    .filter(|(_, cells)| !cells.is_empty())
+                    .collect();
+
+                let bases: Vec<&(Group, CellSet)> = groups.iter().filter(|(_, cells)| cells.size() >= 2).collect();
+
+                for mask in combinations(bases.len(), size) {
+                    let base: Vec<&(Group, CellSet)> = select(&bases, mask).collect();
+
+                    //  The base groups must not overlap, so that the digit appears exactly N times within them.
+                    let base_cells = base.iter().fold(CellSet::empty(), |all, (_, cells)| all.union(cells));
+
+                    if base_cells.size() != base.iter().map(|(_, cells)| cells.size()).sum::<usize>() {
+                        continue;
+                    }
+
+                    let base_groups: GroupSet = base.iter().map(|(group, _)| *group).collect();
+
+                    let covers: Vec<&(Group, CellSet)> = groups.iter()
+                        .filter(|(group, cells)| !base_groups.has(*group) && !cells.intersection(&base_cells).is_empty())
+                        .collect();
+
+                    for mask in combinations(covers.len(), size) {
+                        let cover: Vec<&(Group, CellSet)> = select(&covers, mask).collect();
+
+                        if is_basic(&base, &cover) {
+                            continue;
+                        }
+
+                        let cover_cells = cover.iter().fold(CellSet::empty(), |all, (_, cells)| all.union(cells));
+                        let fins = base_cells.difference(&cover_cells);
+
+                        if fins.size() > self.max_fins {
+                            continue;
+                        }
+
+                        //  Either one of the fins holds the digit, or the fish does: only the cells seeing all the fins
+                        //  can be eliminated.
+                        let targets = fins.into_iter()
+                            .fold(cover_cells.difference(&base_cells), |targets, fin| targets.intersection(&CellSet::peers(fin)));
+
+                        if targets.is_empty() {
+                            continue;
+                        }
+
+                        let cover_groups: GroupSet = cover.iter().map(|(group, _)| *group).collect();
+                        let reason = RefinementReason::Fish(base_groups, cover_groups, fins);
+
+                        if remove_possibilities(possible_values, refinements, targets, digit, &reason) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The orientation of the fish: the type of its base lines.
+#[derive(Clone, Copy, Debug)]
+enum Orientation {
+    Rows,
+    Columns,
+}
+
+impl Orientation {
+    //  Returns the cell at the specified position within the specified base line.
+    fn cell(self, line: usize, position: usize) -> CellIndex {
+        let (row, column) = match self {
+            Orientation::Rows => (line, position),
+            Orientation::Columns => (position, line),
+        };
+
+        cell_at(row, column)
+    }
+
+    fn base(self, line: usize) -> Group {
+        match self {
+            Orientation::Rows => Group::Row(RowIndex::new(line).expect("Valid row")),
+            Orientation::Columns => Group::Column(ColumnIndex::new(line).expect("Valid column")),
+        }
+    }
+
+    fn cover(self, position: usize) -> Group {
+        match self {
+            Orientation::Rows => Group::Column(ColumnIndex::new(position).expect("Valid column")),
+            Orientation::Columns => Group::Row(RowIndex::new(position).expect("Valid row")),
+        }
+    }
+
+    //  Returns, for each base line, the bitmask of the positions at which the digit is possible.
+    fn positions(self, possible_values: &PossibleValues, digit: Digit) -> [u16; DIMENSION] {
+        let mut result = [0; DIMENSION];
+
+        for (line, positions) in result.iter_mut().enumerate() {
+            for position in 0..DIMENSION {
+                if possible_values.of_cell(self.cell(line, position)).has(digit) {
+                    *positions |= 1 << position;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+//  A fish, possibly finned.
+struct FishPattern<'a> {
+    orientation: Orientation,
+    digit: Digit,
+    base: &'a [usize],
+    //  Bitmask of the cover positions.
+    cover: u16,
+    fins: CellSet,
+}
+
+impl FishPattern<'_> {
+    //  Eliminates the digit from the cover lines, outside the base lines, within the targets.
+    //
+    //  Returns whether any possibility was removed.
+    fn eliminate(
+        &self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        targets: CellSet,
+    )
+        -> bool
+    {
+        let base: GroupSet = self.base.iter().map(|line| self.orientation.base(*line)).collect();
+        let cover: GroupSet = (0..DIMENSION)
+            .filter(|position| self.cover & (1 << position) != 0)
+            .map(|position| self.orientation.cover(position))
+            .collect();
+
+        let reason = RefinementReason::Fish(base, cover, self.fins);
+
+        let mut removed = false;
+
+        for line in 0..DIMENSION {
+            if self.base.contains(&line) {
+                continue;
+            }
+
+            for position in 0..DIMENSION {
+                let cell = self.orientation.cell(line, position);
+
+                if self.cover & (1 << position) == 0 || !targets.has(cell) {
+                    continue;
+                }
+
+                removed |= remove_possibility(possible_values, refinements, cell, self.digit, &reason);
+            }
+        }
+
+        removed
+    }
+}
+
+//  Returns all the sets of `size` base lines with at least 2 possible positions each.
+fn base_lines(positions: &[u16; DIMENSION], size: usize) -> impl Iterator<Item = Vec<usize>> {
+    let lines: Vec<usize> = (0..DIMENSION).filter(|line| positions[*line].count_ones() >= 2).collect();
+
+    combinations(lines.len(), size).map(move |mask| {
+        select(&lines, mask).collect()
+    })
+}
+
+//  Returns the square containing all the cells, if any.
+fn single_square(cells: CellSet) -> Option<SquareIndex> {
+    let mut cells = cells.into_iter();
+    let square = cells.next()?.square();
+
+    cells.all(|cell| cell.square() == square).then_some(square)
+}
+
+//  Returns whether the fish is a basic one, rows against columns, already handled by Fish and FinnedFish.
+fn is_basic(base: &[&(Group, CellSet)], cover: &[&(Group, CellSet)]) -> bool {
+    let all_rows = |groups: &[&(Group, CellSet)]| groups.iter().all(|(group, _)| matches!(group, Group::Row(_)));
+    let all_columns = |groups: &[&(Group, CellSet)]| groups.iter().all(|(group, _)| matches!(group, Group::Column(_)));
+
+    (all_rows(base) && all_columns(cover)) || (all_columns(base) && all_rows(cover))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, column, digit, parse_candidates, row, square};
+
+#[test]
+fn x_wing() {
+    //  1 is only possible in c3 and c5 within r2 and r7.
+    let mut possible_values = parse_candidates("
+        89 89 3 4 7 2 6 1 5
+        4 7 12 6 18 5 28 9 3
+        6 12 5 18 9 3 4 7 28
+        58 58 6 2 3 1 7 4 9
+        3 29 279 578 4 78 258 6 1
+        1 4 27 9 568 678 3 258 28
+        7 6 189 3 1258 89 258 258 4
+        25 15 18 1578 12568 4 9 3 67
+        259 3 4 578 2568 6789 1 258 67
+    ");
+    let refinements = JournalWriter::new();
+
+    Fish::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::Fish(
+        [row(2), row(7)].into_iter().collect(),
+        [column(3), column(5)].into_iter().collect(),
+        CellSet::empty());
+
+    assert_eq!(
+        vec![
+            Refinement::new(cell(8, 3), digit(1), reason.clone()),
+            Refinement::new(cell(8, 5), digit(1), reason),
+        ],
+        refinements.reader().get_events()
+    );
+}
+
+#[test]
+fn swordfish() {
+    //  1 is only possible in c3, c8 and c9 within r3, r4 and r8.
+    let mut possible_values = parse_candidates("
+        4 12 6 5789 2578 1278 159 3 1589
+        7 123 1359 3589 258 1238 6 1589 4
+        359 8 1359 3459 56 46 7 159 2
+        29 7 189 58 3 28 4 6 159
+        2369 136 1389 4578 25678 46 159 12579 13579
+        236 5 4 1 267 9 8 27 37
+        356 4 357 2 1 378 59 589 5689
+        8 9 2 6 4 5 3 17 17
+        1 36 357 378 9 378 2 4 568
+    ");
+    let refinements = JournalWriter::new();
+
+    Fish::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::Fish(
+        [row(3), row(4), row(8)].into_iter().collect(),
+        [column(3), column(8), column(9)].into_iter().collect(),
+        CellSet::empty());
+
+    let expected: Vec<Refinement> = [(1, 9), (2, 3), (2, 8), (5, 3), (5, 8), (5, 9)].into_iter()
+        .map(|(row, column)| Refinement::new(cell(row, column), digit(1), reason.clone()))
+        .collect();
+
+    assert_eq!(expected, refinements.reader().get_events());
+}
+
+#[test]
+fn finned_x_wing() {
+    //  5 is only possible in c3 and c5 within r1, and in c3 and c6 within r4: r4c6 is the fin.
+    let mut possible_values = parse_candidates("
+        4 89 58 6 35 7 1 389 2
+        1 2 3 9 8 4 56 7 56
+        57 789 6 135 235 1235 39 4 389
+        3 1 25 4 9 25 8 6 7
+        6 78 9 3578 2357 2358 4 235 1
+        57 4 258 13578 6 12358 2359 2359 359
+        2 3 1 578 57 6 579 589 4
+        8 5 4 37 1 9 2367 23 36
+        9 6 7 2 4 358 35 1 358
+    ");
+    let refinements = JournalWriter::new();
+
+    FinnedFish::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::Fish(
+        [row(1), row(4)].into_iter().collect(),
+        [column(3), column(5)].into_iter().collect(),
+        CellSet::from(cell(4, 6)));
+
+    assert_eq!(vec![Refinement::new(cell(5, 5), digit(5), reason)], refinements.reader().get_events());
+}
+
+#[test]
+fn mutant_swordfish() {
+    //  3 is only possible in r2, s7 and s8 within c3, c6 and r7: a Mutant Swordfish.
+    let candidates = "
+        18 238 7 126 136 5 9 123 4
+        4 5 13 29 7 39 6 1238 28
+        9 23 6 4 13 8 5 123 7
+        2 6 9 3 8 4 1 7 5
+        3 7 8 5 9 1 4 26 26
+        5 1 4 7 2 6 8 9 3
+        18 34 5 16 34 2 7 68 9
+        6 9 2 8 5 7 3 4 1
+        7 48 13 169 146 39 2 5 68
+    ";
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    FrankenFish::default().analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::Fish(
+        [column(3), column(6), row(7)].into_iter().collect(),
+        [row(2), square(7), square(8)].into_iter().collect(),
+        CellSet::empty());
+
+    assert_eq!(vec![Refinement::new(cell(2, 8), digit(3), reason)], refinements.reader().get_events());
+
+    //  A Franken or Mutant X-Wing is not enough.
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    FrankenFish::new(2, DEFAULT_MAX_FRANKEN_FISH_FINS).analyze_grid(&mut possible_values, &refinements);
+
+    assert_eq!(Vec::<Refinement>::new(), refinements.reader().get_events());
+}
+
+}
@@ -0,0 +1,311 @@
+//! Forcing chains, and forcing nets, the last resort.
+//!
+//! Each candidate of a cell, or each position of a digit within a group, is assumed in turn, and singles are propagated
+//! from the assumption. An assumption leading to a contradiction is false, and a candidate removed whichever the
+//! assumption is false as well.
+
+use std::sync::Arc;
+
+use crate::model::{Candidate, CandidateSet, CellIndex, CellSet, DigitSet, Group, GroupIndex};
+use crate::solver::{DEFAULT_MAX_FORCING_BRANCHES, ForcingBranch, ForcingChainKind, ForcingContradiction, ForcingStep, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, remove_possibility};
+
+#[derive(Clone, Debug)]
+pub(super) struct ForcingChain {
+    //  The maximum number of branches, that is candidates of a cell or positions within a group.
+    max_branches: usize,
+}
+
+impl ForcingChain {
+    pub(super) fn new(max_branches: usize) -> Self { Self { max_branches } }
+}
+
+impl Default for ForcingChain {
+    fn default() -> Self { Self::new(DEFAULT_MAX_FORCING_BRANCHES) }
+}
+
+impl AnalysisImpl for ForcingChain {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        //  The fewer the branches, the simpler the explanation.
+        for size in 2..=self.max_branches {
+            for cell in CellIndex::all() {
+                let digits = possible_values.of_cell(cell);
+
+                if digits.size() != size {
+                    continue;
+                }
+
+                let assumptions: Vec<Candidate> = digits.into_iter().map(|digit| Candidate::new(cell, digit)).collect();
+
+                if Self::analyze_assumptions(possible_values, refinements, ForcingChainKind::Cell(cell), &assumptions) {
+                    return;
+                }
+            }
+
+            for group in GroupIndex::all().map(Group::new) {
+                for digit in DigitSet::full() {
+                    if possible_values.of_group(group).count(digit) != size {
+                        continue;
+                    }
+
+                    let assumptions: Vec<Candidate> = group.cells()
+                        .into_iter()
+                        .filter(|cell| possible_values.of_cell(*cell).has(digit))
+                        .map(|cell| Candidate::new(cell, digit))
+                        .collect();
+
+                    let kind = ForcingChainKind::Group(digit, group);
+
+                    if Self::analyze_assumptions(possible_values, refinements, kind, &assumptions) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ForcingChain {
+    //  Returns whether any possibility was removed.
+    fn analyze_assumptions(
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        kind: ForcingChainKind,
+        assumptions: &[Candidate],
+    )
+        -> bool
+    {
+        let propagations: Vec<Propagation> = assumptions.iter()
+            .map(|assumption| Propagation::new(possible_values, *assumption))
+            .collect();
+
+        //  A contradiction eliminates the assumption itself.
+        if let Some(propagation) = propagations.iter().find(|propagation| propagation.contradiction.is_some()) {
+            let assumption = propagation.assumption;
+            let reason = RefinementReason::ForcingChain(ForcingChainKind::Contradiction, Arc::new([propagation.branch()]));
+
+            return remove_possibility(possible_values, refinements, assumption.cell(), assumption.digit(), &reason);
+        }
+
+        //  One of the assumptions is true, hence the candidates removed by all are false.
+        let removed: CandidateSet = candidates(possible_values)
+            .filter(|candidate| propagations.iter().all(|propagation| !propagation.is_possible(*candidate)))
+            .collect();
+
+        if removed.is_empty() {
+            return false;
+        }
+
+        let branches: Arc<[ForcingBranch]> = propagations.iter().map(Propagation::branch).collect();
+        let reason = RefinementReason::ForcingChain(kind, branches);
+
+        let mut result = false;
+
+        for candidate in removed {
+            result |= remove_possibility(possible_values, refinements, candidate.cell(), candidate.digit(), &reason);
+        }
+
+        result
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The propagation of singles from an assumption, on a copy of the possible values.
+pub(super) struct Propagation {
+    assumption: Candidate,
+    possible_values: PossibleValues,
+    //  The cells whose digit was excluded from their peers.
+    settled: CellSet,
+    steps: Vec<ForcingStep>,
+    contradiction: Option<ForcingContradiction>,
+}
+
+impl Propagation {
+    pub(super) fn new(possible_values: &PossibleValues, assumption: Candidate) -> Self {
+        let settled = CellIndex::all().filter(|cell| possible_values.of_cell(*cell).size() == 1).collect();
+
+        let mut result =
+            Self { assumption, possible_values: *possible_values, settled, steps: Vec::new(), contradiction: None };
+
+        result.settle(assumption);
+
+        while result.contradiction.is_none() && result.propagate_next() {}
+
+        result
+    }
+
+    pub(super) fn possible_values(&self) -> &PossibleValues { &self.possible_values }
+
+    pub(super) fn is_contradiction(&self) -> bool { self.contradiction.is_some() }
+
+    fn is_possible(&self, candidate: Candidate) -> bool {
+        self.possible_values.of_cell(candidate.cell()).has(candidate.digit())
+    }
+
+    pub(super) fn branch(&self) -> ForcingBranch {
+        ForcingBranch::new(self.assumption, self.steps.clone(), self.contradiction)
+    }
+
+    //  Propagates the next single, if any.
+    //
+    //  Returns whether a single was found.
+    fn propagate_next(&mut self) -> bool {
+        let naked = CellIndex::all().find(|cell| !self.settled.has(*cell) && self.possible_values.of_cell(*cell).size() == 1);
+
+        if let Some(cell) = naked {
+            let digit = self.possible_values.of_cell(cell).into_iter().next().expect("Single digit");
+            let candidate = Candidate::new(cell, digit);
+
+            self.steps.push(ForcingStep::NakedSingle(candidate));
+            self.settle(candidate);
+
+            return true;
+        }
+
+        for group in GroupIndex::all().map(Group::new) {
+            for digit in DigitSet::full() {
+                if self.possible_values.of_group(group).count(digit) != 1 {
+                    continue;
+                }
+
+                let cell = group.cells()
+                    .into_iter()
+                    .find(|cell| self.possible_values.of_cell(*cell).has(digit))
+                    .expect("1 cell, as per the count above");
+
+                if self.settled.has(cell) {
+                    continue;
+                }
+
+                let candidate = Candidate::new(cell, digit);
+
+                self.steps.push(ForcingStep::HiddenSingle(candidate, group));
+                self.settle(candidate);
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //  Places the candidate, excluding its digit from the peers of its cell, and checks for contradictions.
+    fn settle(&mut self, candidate: Candidate) {
+        let (cell, digit) = (candidate.cell(), candidate.digit());
+
+        self.possible_values.resolve(cell, digit);
+        self.settled.add(cell);
+
+        for peer in CellSet::peers(cell) {
+            self.possible_values.remove_possibility(peer, digit);
+        }
+
+        self.contradiction = self.find_contradiction();
+    }
+
+    fn find_contradiction(&self) -> Option<ForcingContradiction> {
+        if let Some(cell) = CellIndex::all().find(|cell| self.possible_values.of_cell(*cell).is_empty()) {
+            return Some(ForcingContradiction::EmptyCell(cell));
+        }
+
+        GroupIndex::all().map(Group::new).find_map(|group| {
+            DigitSet::full().into_iter()
+                .find(|digit| self.possible_values.of_group(group).count(*digit) == 0)
+                .map(|digit| ForcingContradiction::MissingDigit(digit, group))
+        })
+    }
+}
+
+//  Returns the candidates of the unsolved cells.
+fn candidates(possible_values: &PossibleValues) -> impl Iterator<Item = Candidate> + '_ {
+    CellIndex::all()
+        .filter(|cell| possible_values.of_cell(*cell).size() > 1)
+        .flat_map(|cell| possible_values.of_cell(cell).into_iter().map(move |digit| Candidate::new(cell, digit)))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+//  Whichever of 2, 3 or 8 is in r2c8, 8 is removed from r2c7.
+const THREE_BRANCHES: &str = "
+    7 34 2 18 9 5 6 1348 1348
+    39 5 1 6 4 28 2389 238 7
+    49 8 6 12 7 3 1249 5 149
+    458 29 3 29 6 14 7 148 1458
+    468 27 4789 5 3 1249 1489 16 14689
+    1 69 459 7 8 49 3459 346 2
+    368 1 79 4 5 79 238 2368 368
+    34568 3467 458 38 2 78 1348 9 134568
+    2 349 4589 389 1 6 3458 7 3458
+";
+
+#[test]
+fn cell_forcing_chain() {
+    //  Whichever of 3 or 9 is in r2c1, 3 is removed from r2c3.
+    let mut possible_values = parse_candidates("
+        7 2 8 5 1 4 6 3 9
+        39 349 349 6 2 8 1 7 5
+        1 5 6 7 3 9 4 2 8
+        5 1389 139 19 4 6 2 89 7
+        89 17 127 3 5 127 89 4 6
+        4 6 279 29 8 27 3 5 1
+        6 4789 479 28 79 23 5 1 34
+        389 1389 139 4 6 5 7 89 2
+        2 4789 5 18 79 13 89 6 34
+    ");
+    let refinements = JournalWriter::new();
+
+    ForcingChain::default().analyze_grid(&mut possible_values, &refinements);
+
+    let refinements = refinements.reader().get_events();
+
+    assert_eq!(1, refinements.len());
+    assert_eq!((cell(2, 3), digit(3)), (refinements[0].cell(), refinements[0].removed()));
+    assert_cell_branches(&refinements[0], cell(2, 1), &[3, 9]);
+}
+
+#[test]
+fn max_branches() {
+    let mut possible_values = parse_candidates(THREE_BRANCHES);
+    let refinements = JournalWriter::new();
+
+    ForcingChain::new(2).analyze_grid(&mut possible_values, &refinements);
+
+    assert!(refinements.reader().is_empty());
+
+    ForcingChain::new(3).analyze_grid(&mut possible_values, &refinements);
+
+    let refinements = refinements.reader().get_events();
+
+    assert_eq!(1, refinements.len());
+    assert_eq!((cell(2, 7), digit(8)), (refinements[0].cell(), refinements[0].removed()));
+    assert_cell_branches(&refinements[0], cell(2, 8), &[2, 3, 8]);
+}
+
+//  Asserts the refinement follows from a cell forcing chain, assuming each of the digits in turn, none leading to a
+//  contradiction.
+fn assert_cell_branches(refinement: &Refinement, assumed: CellIndex, digits: &[usize]) {
+    let RefinementReason::ForcingChain(kind, branches) = refinement.reason() else {
+        panic!("Unexpected reason {:?}", refinement.reason())
+    };
+
+    let assumptions: Vec<Candidate> = branches.iter().map(ForcingBranch::assumption).collect();
+    let expected: Vec<Candidate> = digits.iter().map(|value| Candidate::new(assumed, digit(*value))).collect();
+
+    assert_eq!(ForcingChainKind::Cell(assumed), *kind);
+    assert_eq!(expected, assumptions);
+    assert!(branches.iter().all(|branch| branch.contradiction().is_none()));
+}
+
+}
@@ -0,0 +1,354 @@
+//! 3D Medusa: coloring of the candidates along strong links.
+//!
+//! Within a cluster of candidates connected by strong links, alternating colors are given to the candidates, so that
+//! either all candidates of one color are true, or all candidates of the other are.
+
+use crate::model::{Candidate, CandidateSet, CellIndex, CellSet, Digit, DigitSet, Group, GroupIndex};
+use crate::solver::{JournalWriter, MedusaRule, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, CandidateGraph, remove_possibility};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct Medusa;
+
+impl AnalysisImpl for Medusa {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        let graph = CandidateGraph::new(possible_values);
+
+        let mut visited = CandidateSet::empty();
+
+        for candidate in graph.candidates() {
+            if visited.has(candidate) || graph.strong_links(candidate).is_empty() {
+                continue;
+            }
+
+            let colors = color(&graph, candidate);
+            visited = visited.union(&colors[0]).union(&colors[1]);
+
+            let cluster = Cluster { graph: &graph, colors };
+
+            if cluster.analyze(possible_values, refinements) {
+                return;
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  Colors the cluster of the candidate, by alternating colors along strong links.
+fn color(graph: &CandidateGraph, start: Candidate) -> [CandidateSet; 2] {
+    let mut colors = [CandidateSet::from(start), CandidateSet::empty()];
+    let mut queue = vec![(start, 0)];
+
+    while let Some((candidate, color)) = queue.pop() {
+        for linked in graph.strong_links(candidate) {
+            if colors[0].has(*linked) || colors[1].has(*linked) {
+                continue;
+            }
+
+            colors[1 - color].add(*linked);
+            queue.push((*linked, 1 - color));
+        }
+    }
+
+    colors
+}
+
+struct Cluster<'a> {
+    graph: &'a CandidateGraph,
+    colors: [CandidateSet; 2],
+}
+
+impl Cluster<'_> {
+    //  Returns whether any possibility was removed.
+    fn analyze(&self, possible_values: &mut PossibleValues, refinements: &JournalWriter<Refinement>) -> bool {
+        //  Rules proving a color false, hence all its candidates.
+        for color in 0..2 {
+            let rule = if self.is_twice_in_cell(color) {
+                MedusaRule::TwiceInCell
+            } else if self.is_twice_in_group(color) {
+                MedusaRule::TwiceInGroup
+            } else {
+                continue;
+            };
+
+            if self.remove_color(possible_values, refinements, rule, color) {
+                return true;
+            }
+        }
+
+        //  Rules eliminating uncolored candidates.
+        let eliminations = [
+            (MedusaRule::TwoColorsInCell, self.two_colors_in_cell()),
+            (MedusaRule::TwoColorsElsewhere, self.two_colors_elsewhere()),
+            (MedusaRule::TwoColorsGroupAndCell, self.two_colors_group_and_cell()),
+        ];
+
+        for (rule, candidates) in eliminations {
+            let reason = RefinementReason::Medusa(rule, self.colors[0], self.colors[1]);
+
+            let mut removed = false;
+
+            for candidate in candidates {
+                removed |= remove_possibility(possible_values, refinements, candidate.cell(), candidate.digit(), &reason);
+            }
+
+            if removed {
+                return true;
+            }
+        }
+
+        //  Last rule proving a color false.
+        for color in 0..2 {
+            if self.is_emptying_cell(possible_values, color)
+                && self.remove_color(possible_values, refinements, MedusaRule::CellEmptiedByColor, color)
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //  Rule 1: two candidates of the same color within a cell.
+    fn is_twice_in_cell(&self, color: usize) -> bool {
+        let mut seen = CellSet::empty();
+
+        for candidate in self.colors[color] {
+            if seen.has(candidate.cell()) {
+                return true;
+            }
+
+            seen.add(candidate.cell());
+        }
+
+        false
+    }
+
+    //  Rule 2: two candidates of the same color and digit within a group.
+    fn is_twice_in_group(&self, color: usize) -> bool {
+        let groups: Vec<CellSet> = GroupIndex::all().map(|group| Group::new(group).cells().into_iter().collect()).collect();
+
+        DigitSet::full().into_iter().any(|digit| {
+            let cells = self.colors[color].of_digit(digit);
+
+            groups.iter().any(|group| group.intersection(&cells).size() > 1)
+        })
+    }
+
+    //  Rule 3: a cell with candidates of both colors cannot hold any uncolored candidate.
+    fn two_colors_in_cell(&self) -> CandidateSet {
+        let all = self.graph.candidates();
+        let colored = self.colors[0].union(&self.colors[1]);
+
+        let cells_of = |color: usize| DigitSet::full().into_iter()
+            .fold(CellSet::empty(), |cells, digit| cells.union(&self.colors[color].of_digit(digit)));
+        let cells = cells_of(0).intersection(&cells_of(1));
+
+        all.difference(&colored).into_iter().filter(|candidate| cells.has(candidate.cell())).collect()
+    }
+
+    //  Rule 4: an uncolored candidate seeing candidates of both colors with the same digit.
+    fn two_colors_elsewhere(&self) -> CandidateSet {
+        let all = self.graph.candidates();
+        let colored = self.colors[0].union(&self.colors[1]);
+
+        all.difference(&colored)
+            .into_iter()
+            .filter(|candidate| (0..2).all(|color| self.seen_by_color(color, candidate.digit()).has(candidate.cell())))
+            .collect()
+    }
+
+    //  Rule 5: an uncolored candidate in a cell with a candidate of one color, seeing a candidate of the other color
+    //  with the same digit.
+    fn two_colors_group_and_cell(&self) -> CandidateSet {
+        let all = self.graph.candidates();
+        let colored = self.colors[0].union(&self.colors[1]);
+
+        all.difference(&colored)
+            .into_iter()
+            .filter(|candidate| {
+                (0..2).any(|color| {
+                    let in_cell = DigitSet::full().into_iter()
+                        .any(|digit| self.colors[color].has(Candidate::new(candidate.cell(), digit)));
+
+                    in_cell && self.seen_by_color(1 - color, candidate.digit()).has(candidate.cell())
+                })
+            })
+            .collect()
+    }
+
+    //  Rule 6: an uncolored cell, all candidates of which see a candidate of the same color with the same digit.
+    fn is_emptying_cell(&self, possible_values: &PossibleValues, color: usize) -> bool {
+        let colored = self.colors[0].union(&self.colors[1]);
+
+        CellIndex::all().any(|cell| {
+            let digits = possible_values.of_cell(cell);
+
+            digits.size() > 1
+                && digits.into_iter().all(|digit| !colored.has(Candidate::new(cell, digit)))
+                && digits.into_iter().all(|digit| self.seen_by_color(color, digit).has(cell))
+        })
+    }
+
+    //  Returns the cells seeing a candidate of the specified color and digit.
+    fn seen_by_color(&self, color: usize, digit: Digit) -> CellSet {
+        self.graph.seen_by_any(self.colors[color].of_digit(digit))
+    }
+
+    fn remove_color(
+        &self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        rule: MedusaRule,
+        color: usize,
+    )
+        -> bool
+    {
+        let reason = RefinementReason::Medusa(rule, self.colors[color], self.colors[1 - color]);
+
+        let mut removed = false;
+
+        for candidate in self.colors[color] {
+            removed |= remove_possibility(possible_values, refinements, candidate.cell(), candidate.digit(), &reason);
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+#[test]
+fn twice_in_cell() {
+    let expected = [
+        (1, 1, 1), (2, 8, 1), (7, 4, 1), (9, 3, 1), (2, 9, 2), (5, 8, 2), (2, 3, 3), (5, 9, 6), (7, 8, 6), (1, 2, 8),
+        (2, 8, 8), (7, 1, 8), (9, 9, 8),
+    ];
+
+    check(MedusaRule::TwiceInCell, &expected, "
+        18 238 7 126 136 5 9 123 4
+        4 5 13 29 7 39 6 1238 28
+        9 23 6 4 13 8 5 123 7
+        2 6 9 3 8 4 1 7 5
+        3 7 8 5 9 1 4 26 26
+        5 1 4 7 2 6 8 9 3
+        18 34 5 16 34 2 7 68 9
+        6 9 2 8 5 7 3 4 1
+        7 348 13 169 1346 39 2 5 68
+    ");
+}
+
+#[test]
+fn twice_in_group() {
+    let expected = [(4, 2, 8), (5, 7, 8), (8, 1, 8), (8, 8, 8), (4, 8, 9), (5, 1, 9), (9, 7, 9)];
+
+    check(MedusaRule::TwiceInGroup, &expected, "
+        7 2 8 5 1 4 6 3 9
+        39 349 349 6 2 8 1 7 5
+        1 5 6 7 3 9 4 2 8
+        5 1389 139 19 4 6 2 89 7
+        89 17 127 3 5 127 89 4 6
+        4 6 279 29 8 27 3 5 1
+        6 4789 479 28 79 23 5 1 34
+        389 1389 139 4 6 5 7 89 2
+        2 4789 5 18 79 13 89 6 34
+    ");
+}
+
+#[test]
+fn two_colors_in_cell() {
+    check(MedusaRule::TwoColorsInCell, &[(2, 8, 1)], "
+        134 2 34 47 5 6 8 9 17
+        14 9 5 47 12 8 3 127 6
+        8 6 7 3 9 12 4 12 5
+        5 8 29 1 4 23 6 37 79
+        6 34 1 9 8 7 5 34 2
+        34 7 29 6 23 5 1 348 89
+        9 1 6 8 7 34 2 5 34
+        7 5 34 2 6 134 9 18 1348
+        2 34 8 5 13 9 7 6 134
+    ");
+}
+
+#[test]
+fn two_colors_elsewhere() {
+    check(MedusaRule::TwoColorsElsewhere, &[(2, 4, 1)], "
+        18 238 7 126 136 5 9 123 4
+        4 5 13 129 7 39 6 1238 28
+        9 23 6 4 13 8 5 123 7
+        2 6 9 3 8 4 1 7 5
+        3 7 8 5 9 1 4 26 26
+        5 1 4 7 2 6 8 9 3
+        18 34 5 16 34 2 7 68 9
+        6 9 2 8 5 7 3 4 1
+        7 348 13 169 1346 39 2 5 68
+    ");
+}
+
+#[test]
+fn two_colors_group_and_cell() {
+    check(MedusaRule::TwoColorsGroupAndCell, &[(6, 2, 2)], "
+        34 34 5 8 2 7 1 9 6
+        6 7 1 4 3 9 5 2 8
+        28 9 28 56 1 56 4 3 7
+        5 23 7 1 6 8 23 4 9
+        1 8 269 29 4 3 26 7 5
+        239 236 4 259 7 25 8 16 13
+        48 1 368 7 9 46 36 5 2
+        249 246 2369 236 5 1 7 8 34
+        7 5 23 236 8 246 9 16 134
+    ");
+}
+
+#[test]
+fn cell_emptied_by_color() {
+    check(MedusaRule::CellEmptiedByColor, &[(8, 9, 3), (2, 7, 5), (2, 9, 6), (8, 7, 6)], "
+        4 89 58 6 35 7 1 389 2
+        1 2 3 9 8 4 56 7 56
+        57 789 6 135 235 1235 39 4 389
+        3 1 25 4 9 25 8 6 7
+        6 78 9 3578 2357 2358 4 235 1
+        57 4 258 13578 6 12358 2359 2359 359
+        2 3 1 578 57 6 579 589 4
+        8 5 4 37 1 9 2367 23 36
+        9 6 7 2 4 358 35 1 358
+    ");
+}
+
+//  Checks that the rule applies to the first cluster, and removes exactly the expected candidates: row, column, digit.
+fn check(rule: MedusaRule, expected: &[(usize, usize, usize)], candidates: &str) {
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    Medusa.analyze_grid(&mut possible_values, &refinements);
+
+    let refinements = refinements.reader().get_events();
+
+    let removed: Vec<(CellIndex, Digit)> = refinements.iter()
+        .map(|refinement| (refinement.cell(), refinement.removed()))
+        .collect();
+    let expected: Vec<(CellIndex, Digit)> = expected.iter()
+        .map(|(row, column, removed)| (cell(*row, *column), digit(*removed)))
+        .collect();
+
+    assert_eq!(expected, removed);
+
+    for refinement in &refinements {
+        assert_eq!(refinements[0].reason(), refinement.reason());
+        assert!(matches!(refinement.reason(), RefinementReason::Medusa(actual, ..) if *actual == rule));
+    }
+}
+
+}
@@ -0,0 +1,265 @@
+//! Nishio: the trial of a single digit.
+//!
+//! A digit is assumed in a cell, and only the placements of this digit are propagated: should the positions left be
+//! unable to fit the remaining copies of the digit, the assumption is false.
+//!
+//! The positions fit when every group has a position left, and when each row left can hold the digit in a distinct
+//! column left, that is when the rows and columns left have a perfect matching. Squares are only checked individually:
+//! a complete check, one copy per row, column and square, is left to `Analysis::PatternOverlay`.
+
+use std::sync::Arc;
+
+use crate::model::{DIMENSION, CellIndex, CellSet, DigitSet, Group, GroupIndex, GroupSet, RowIndex};
+use crate::solver::{DEFAULT_NISHIO_DEPTH, DEFAULT_NISHIO_EFFORT, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, remove_possibility};
+
+#[derive(Clone, Debug)]
+pub(super) struct Nishio {
+    depth: usize,
+    effort: usize,
+}
+
+impl Nishio {
+    pub(super) fn new(depth: usize, effort: usize) -> Self { Self { depth, effort } }
+}
+
+impl Default for Nishio {
+    fn default() -> Self { Self::new(DEFAULT_NISHIO_DEPTH, DEFAULT_NISHIO_EFFORT) }
+}
+
+impl AnalysisImpl for Nishio {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        let mut effort = 0;
+
+        for digit in DigitSet::full() {
+            let positions: CellSet = CellIndex::all().filter(|cell| possible_values.of_cell(*cell).has(digit)).collect();
+            let placed: CellSet = positions.into_iter().filter(|cell| possible_values.of_cell(*cell).size() == 1).collect();
+
+            for cell in positions.difference(&placed) {
+                if effort == self.effort {
+                    return;
+                }
+
+                effort += 1;
+
+                let Some((placements, groups)) = self.trial(positions, placed, cell) else { continue };
+
+                let reason = RefinementReason::Nishio(placements, groups);
+
+                if remove_possibility(possible_values, refinements, cell, digit, &reason) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Nishio {
+    //  Propagates the placements of the digit from the assumption, up to the maximum depth.
+    //
+    //  Returns the placements, starting with the assumption, and the groups which cannot all hold the digit, on
+    //  contradiction.
+    fn trial(
+        &self,
+        positions: CellSet,
+        placed: CellSet,
+        assumption: CellIndex,
+    )
+        -> Option<(Arc<[CellIndex]>, GroupSet)>
+    {
+        let mut placed = placed;
+        let mut available = positions.difference(&placed);
+        let mut placements = Vec::new();
+
+        let mut next = Some(assumption);
+
+        while let Some(cell) = next {
+            placed.add(cell);
+            available = available.difference(&CellSet::peers(cell));
+            available.remove(cell);
+            placements.push(cell);
+
+            let open: Vec<(Group, CellSet)> = GroupIndex::all()
+                .map(Group::new)
+                .map(|group| (group, group.cells().into_iter().collect::<CellSet>()))
+                .filter(|(_, cells)| cells.intersection(&placed).is_empty())
+                .collect();
+
+            if let Some((group, _)) = open.iter().find(|(_, cells)| cells.intersection(&available).is_empty()) {
+                return Some((placements.into(), [*group].into_iter().collect()));
+            }
+
+            if let Some(rows) = unmatched_rows(available, placed) {
+                return Some((placements.into(), rows));
+            }
+
+            if placements.len() > self.depth {
+                return None;
+            }
+
+            //  A group with a single position left for the digit forces its placement.
+            next = open.iter()
+                .map(|(_, cells)| cells.intersection(&available))
+                .find(|cells| cells.size() == 1)
+                .and_then(|cells| cells.into_iter().next());
+        }
+
+        None
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  Matches the rows left with the columns left, each row with a column in which the digit is available.
+//
+//  Returns, should there be no perfect matching, rows whose positions lie within fewer columns than rows.
+fn unmatched_rows(available: CellSet, placed: CellSet) -> Option<GroupSet> {
+    //  For each row left, the columns in which the digit is available.
+    let mut columns = [0u16; DIMENSION];
+
+    for cell in available {
+        columns[cell.row().value()] |= 1 << cell.column().value();
+    }
+
+    let open: Vec<usize> = RowIndex::all()
+        .map(|row| row.value())
+        .filter(|row| placed.into_iter().all(|cell| cell.row().value() != *row))
+        .collect();
+
+    //  For each column, the row it is matched with.
+    let mut matched = [None; DIMENSION];
+
+    for &row in &open {
+        let mut visited = 0u16;
+
+        if !augment(&columns, &mut matched, &mut visited, row) {
+            //  The search went through every alternating path from the row: the columns it visited are all matched,
+            //  with rows which, together with this one, only have positions within these columns.
+            let rows = (0..DIMENSION)
+                .filter(|column| visited & (1 << column) != 0)
+                .filter_map(|column| matched[column])
+                .chain([row])
+                .map(|row| Group::Row(RowIndex::new(row).expect("Valid RowIndex")))
+                .collect();
+
+            return Some(rows);
+        }
+    }
+
+    None
+}
+
+//  Searches, depth-first, for an augmenting path from the row, marking the columns visited.
+//
+//  Returns whether the row could be matched, possibly re-matching the rows already matched.
+fn augment(
+    columns: &[u16; DIMENSION],
+    matched: &mut [Option<usize>; DIMENSION],
+    visited: &mut u16,
+    row: usize,
+)
+    -> bool
+{
+    for column in 0..DIMENSION {
+        if columns[row] & (1 << column) == 0 || *visited & (1 << column) != 0 {
+            continue;
+        }
+
+        *visited |= 1 << column;
+
+        if matched[column].is_none_or(|other| augment(columns, matched, visited, other)) {
+            matched[column] = Some(row);
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates, row};
+
+//  5 in r3c6 leaves r1 and r4 with 5 in c3 only, although neither is left without a position.
+const UNMATCHED: &str = "
+    4 89 58 6 35 7 1 389 2
+    1 2 3 9 8 4 56 7 56
+    57 789 6 135 235 1235 39 4 389
+    3 1 25 4 9 25 8 6 7
+    6 78 9 3578 2357 2358 4 235 1
+    57 4 258 13578 6 12358 2359 2359 359
+    2 3 1 578 57 6 579 589 4
+    8 5 4 37 1 9 2367 23 36
+    9 6 7 2 4 358 35 1 358
+";
+
+//  3 in r1c3 forces 3 in r6c1, after which r4, r5, r7 and r8 are left with 3 in c6, c8 and c9 only.
+const PROPAGATED: &str = "
+    134 2 34 47 5 6 8 9 17
+    14 9 5 47 12 8 3 127 6
+    8 6 7 3 9 12 4 12 5
+    5 8 29 1 4 23 6 37 79
+    6 34 1 9 8 7 5 34 2
+    34 7 29 6 23 5 1 348 89
+    9 1 6 8 7 34 2 5 34
+    7 5 34 2 6 134 9 18 1348
+    2 34 8 5 13 9 7 6 134
+";
+
+#[test]
+fn matching() {
+    let refinements = analyze(Nishio::default(), UNMATCHED);
+
+    let reason = RefinementReason::Nishio([cell(3, 6)].into(), [row(1), row(4)].into_iter().collect());
+
+    assert_eq!(vec![Refinement::new(cell(3, 6), digit(5), reason)], refinements);
+}
+
+#[test]
+fn depth() {
+    //  Without propagation, the contradiction is only found from r6c1.
+    let refinements = analyze(Nishio::new(0, DEFAULT_NISHIO_EFFORT), PROPAGATED);
+
+    let rows = [row(1), row(4), row(5), row(7), row(8)].into_iter().collect();
+    let reason = RefinementReason::Nishio([cell(6, 1)].into(), rows);
+
+    assert_eq!(vec![Refinement::new(cell(6, 1), digit(3), reason)], refinements);
+
+    let refinements = analyze(Nishio::new(1, DEFAULT_NISHIO_EFFORT), PROPAGATED);
+
+    assert_eq!(vec![Refinement::new(cell(1, 3), digit(3), propagated())], refinements);
+}
+
+#[test]
+fn effort() {
+    //  r1c3 is the 22nd assumption: 1 and 2 are tried first.
+    assert_eq!(Vec::<Refinement>::new(), analyze(Nishio::new(DEFAULT_NISHIO_DEPTH, 21), PROPAGATED));
+
+    let refinements = analyze(Nishio::new(DEFAULT_NISHIO_DEPTH, 22), PROPAGATED);
+
+    assert_eq!(vec![Refinement::new(cell(1, 3), digit(3), propagated())], refinements);
+}
+
+fn analyze(mut nishio: Nishio, candidates: &str) -> Vec<Refinement> {
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    nishio.analyze_grid(&mut possible_values, &refinements);
+
+    refinements.reader().get_events()
+}
+
+fn propagated() -> RefinementReason {
+    RefinementReason::Nishio([cell(1, 3), cell(6, 1)].into(), [row(4), row(5), row(7), row(8)].into_iter().collect())
+}
+
+}
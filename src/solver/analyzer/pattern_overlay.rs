@@ -0,0 +1,159 @@
+//! Pattern Overlay Method: the templates of a digit.
+//!
+//! A template is a placement of all 9 instances of a digit, one per row, column, and square, consistent with the
+//! possible values. The solution is one of the templates, hence a candidate within no template is false, and a
+//! candidate within every template is true.
+
+use crate::model::{DIMENSION, SQUARE_DIMENSION, Digit, DigitSet};
+use crate::solver::{JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, cell_at, remove_possibility};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct PatternOverlay;
+
+impl AnalysisImpl for PatternOverlay {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for digit in DigitSet::full() {
+            let templates = Templates::new(possible_values, digit);
+
+            if templates.count == 0 {
+                continue;
+            }
+
+            let reason = RefinementReason::PatternOverlay(digit, templates.count);
+
+            let mut removed = false;
+
+            for row in 0..DIMENSION {
+                for column in 0..DIMENSION {
+                    let cell = cell_at(row, column);
+                    let bit = 1 << column;
+
+                    //  Within no template: the digit cannot be there.
+                    if templates.union[row] & bit == 0 {
+                        removed |= remove_possibility(possible_values, refinements, cell, digit, &reason);
+                    }
+
+                    //  Within every template: the digit must be there.
+                    if templates.intersection[row] & bit != 0 {
+                        for other in possible_values.of_cell(cell) {
+                            if other != digit {
+                                removed |= remove_possibility(possible_values, refinements, cell, other, &reason);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if removed {
+                return;
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The overlay of all the templates of a digit, as bitmasks of columns per row.
+struct Templates {
+    count: usize,
+    union: [u16; DIMENSION],
+    intersection: [u16; DIMENSION],
+}
+
+impl Templates {
+    fn new(possible_values: &PossibleValues, digit: Digit) -> Self {
+        let mut positions = [0u16; DIMENSION];
+
+        for (row, positions) in positions.iter_mut().enumerate() {
+            for column in 0..DIMENSION {
+                if possible_values.of_cell(cell_at(row, column)).has(digit) {
+                    *positions |= 1 << column;
+                }
+            }
+        }
+
+        let mut result = Self { count: 0, union: [0; DIMENSION], intersection: [u16::MAX; DIMENSION] };
+        let mut template = [0u16; DIMENSION];
+
+        result.enumerate(&positions, &mut template, 0, 0, 0);
+
+        result
+    }
+
+    //  Enumerates the templates, one row at a time, given the columns and squares already used.
+    fn enumerate(
+        &mut self,
+        positions: &[u16; DIMENSION],
+        template: &mut [u16; DIMENSION],
+        row: usize,
+        columns: u16,
+        squares: u16,
+    )
+    {
+        if row == DIMENSION {
+            self.count += 1;
+
+            for (row, bit) in template.iter().enumerate() {
+                self.union[row] |= bit;
+                self.intersection[row] &= bit;
+            }
+
+            return;
+        }
+
+        for column in 0..DIMENSION {
+            let bit = 1 << column;
+            let square = 1 << ((row / SQUARE_DIMENSION) * SQUARE_DIMENSION + column / SQUARE_DIMENSION);
+
+            if positions[row] & bit == 0 || columns & bit != 0 || squares & square != 0 {
+                continue;
+            }
+
+            template[row] = bit;
+
+            self.enumerate(positions, template, row + 1, columns | bit, squares | square);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+#[test]
+fn pattern_overlay() {
+    //  None of the 14 templates of 5 go through r3c6, or r5c5.
+    let mut possible_values = parse_candidates("
+        4 89 58 6 35 7 1 389 2
+        1 2 3 9 8 4 56 7 56
+        57 789 6 135 235 1235 39 4 389
+        3 1 25 4 9 25 8 6 7
+        6 78 9 3578 2357 2358 4 235 1
+        57 4 258 13578 6 12358 2359 2359 359
+        2 3 1 578 57 6 579 589 4
+        8 5 4 37 1 9 2367 23 36
+        9 6 7 2 4 358 35 1 358
+    ");
+    let refinements = JournalWriter::new();
+
+    PatternOverlay.analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::PatternOverlay(digit(5), 14);
+
+    assert_eq!(
+        vec![Refinement::new(cell(3, 6), digit(5), reason.clone()), Refinement::new(cell(5, 5), digit(5), reason)],
+        refinements.reader().get_events()
+    );
+}
+
+}
@@ -0,0 +1,172 @@
+//! Sue de Coq, a generalization of the overlap between a square and a line.
+//!
+//! A set of N cells within the overlap of a square and a line, with at least N+2 possible digits, is combined with an
+//! ALS in the rest of the line and another in the rest of the square, so that the cells altogether form a locked set.
+
+use crate::model::{CellIndex, CellSet, DigitSet, Group, SquareIndex};
+use crate::solver::{AlmostLockedSet, JournalWriter, PossibleValues, Refinement, RefinementReason};
+use super::{AnalysisImpl, combinations, remove_possibilities, select};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct SueDeCoq;
+
+impl AnalysisImpl for SueDeCoq {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for square in SquareIndex::all() {
+            let lines = square.rows().map(Group::Row).chain(square.columns().map(Group::Column));
+
+            for line in lines.collect::<Vec<_>>() {
+                if Self::analyze_overlap(possible_values, refinements, Group::Square(square), line) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl SueDeCoq {
+    //  Returns whether any possibility was removed.
+    fn analyze_overlap(
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        square: Group,
+        line: Group,
+    )
+        -> bool
+    {
+        let overlap: Vec<CellIndex> = line.cells()
+            .into_iter()
+            .filter(|cell| square.contains(*cell) && possible_values.of_cell(*cell).size() > 1)
+            .collect();
+
+        for size in 2..=overlap.len() {
+            for mask in combinations(overlap.len(), size) {
+                let cells: CellSet = select(&overlap, mask).collect();
+                let digits = digits_of(possible_values, cells);
+
+                if digits.size() < size + 2 {
+                    continue;
+                }
+
+                let line_sets = almost_locked_sets(possible_values, line, square, digits);
+                let square_sets = almost_locked_sets(possible_values, square, line, digits);
+
+                for line_set in &line_sets {
+                    for square_set in &square_sets {
+                        if !line_set.digits().intersection(&square_set.digits()).is_empty() {
+                            continue;
+                        }
+
+                        let all = digits.union(&line_set.digits()).union(&square_set.digits());
+
+                        if all.size() != size + line_set.cells().size() + square_set.cells().size() {
+                            continue;
+                        }
+
+                        let reason = RefinementReason::SueDeCoq(cells, *line_set, *square_set);
+
+                        //  Each digit appears exactly once within the cells, hence those which cannot be in the
+                        //  square ALS are in the line, and those which cannot be in the line ALS are in the square.
+                        let line_digits = line_set.digits().union(&digits.difference(&square_set.digits()));
+                        let square_digits = square_set.digits().union(&digits.difference(&line_set.digits()));
+
+                        let line_targets = CellSet::from(line).difference(&cells).difference(&line_set.cells());
+                        let square_targets = CellSet::from(square).difference(&cells).difference(&square_set.cells());
+
+                        let mut removed = false;
+
+                        for digit in line_digits {
+                            removed |= remove_possibilities(possible_values, refinements, line_targets, digit, &reason);
+                        }
+
+                        for digit in square_digits {
+                            removed |= remove_possibilities(possible_values, refinements, square_targets, digit, &reason);
+                        }
+
+                        if removed {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  Returns the ALSs within the group, outside the other group, sharing at least one digit with the specified digits.
+fn almost_locked_sets(possible_values: &PossibleValues, group: Group, other: Group, digits: DigitSet) -> Vec<AlmostLockedSet> {
+    let candidates: Vec<CellIndex> = group.cells()
+        .into_iter()
+        .filter(|cell| !other.contains(*cell) && possible_values.of_cell(*cell).size() > 1)
+        .collect();
+
+    let mut result = Vec::new();
+
+    for size in 1..=candidates.len() {
+        for mask in combinations(candidates.len(), size) {
+            let cells: CellSet = select(&candidates, mask).collect();
+            let set_digits = digits_of(possible_values, cells);
+
+            if set_digits.size() == size + 1 && !set_digits.intersection(&digits).is_empty() {
+                result.push(AlmostLockedSet::new(cells, set_digits));
+            }
+        }
+    }
+
+    result
+}
+
+fn digits_of(possible_values: &PossibleValues, cells: CellSet) -> DigitSet {
+    cells.into_iter().fold(DigitSet::default(), |digits, cell| digits.union(&possible_values.of_cell(cell)))
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::{cell, digit, parse_candidates};
+
+#[test]
+fn sue_de_coq() {
+    //  r6c1 + r6c3 hold 2, 5, 7 and 8, combined with r6c7 + r6c8 + r6c9 in the row, and r5c2 in the square.
+    let mut possible_values = parse_candidates("
+        4 89 58 6 35 7 1 389 2
+        1 2 3 9 8 4 56 7 56
+        57 789 6 135 235 1235 39 4 389
+        3 1 25 4 9 25 8 6 7
+        6 78 9 3578 2357 2358 4 235 1
+        57 4 258 13578 6 12358 2359 2359 359
+        2 3 1 578 57 6 579 589 4
+        8 5 4 37 1 9 2367 23 36
+        9 6 7 2 4 358 35 1 358
+    ");
+    let refinements = JournalWriter::new();
+
+    SueDeCoq.analyze_grid(&mut possible_values, &refinements);
+
+    let reason = RefinementReason::SueDeCoq(
+        [cell(6, 1), cell(6, 3)].into_iter().collect(),
+        AlmostLockedSet::new(
+            [cell(6, 7), cell(6, 8), cell(6, 9)].into_iter().collect(),
+            [2, 3, 5, 9].into_iter().map(digit).collect()),
+        AlmostLockedSet::new(CellSet::from(cell(5, 2)), [digit(7), digit(8)].into_iter().collect()));
+
+    let expected: Vec<Refinement> = [(6, 6, 2), (6, 4, 3), (6, 6, 3), (6, 4, 5), (6, 6, 5)].into_iter()
+        .map(|(row, column, removed)| Refinement::new(cell(row, column), digit(removed), reason.clone()))
+        .collect();
+
+    assert_eq!(expected, refinements.reader().get_events());
+}
+
+}
@@ -0,0 +1,744 @@
+//! Unique and avoidable rectangles, both relying on the uniqueness of the solution.
+//!
+//! A rectangle is formed by 4 cells, spread over exactly 2 rows, 2 columns, and 2 squares. Should such a rectangle be
+//! solved with the digits `a` and `b` on its diagonals, the digits could be swapped without breaking any rule, and the
+//! grid would have two solutions: a deadly pattern.
+
+use crate::model::{CellIndex, CellSet, ColumnIndex, Digit, DigitSet, Grid, Group, RowIndex, SQUARE_DIMENSION};
+use crate::solver::{JournalWriter, PossibleValues, Refinement, RefinementReason, UniqueRectangleKind};
+use super::{AnalysisImpl, remove_possibility, select};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct UniqueRectangle;
+
+impl AnalysisImpl for UniqueRectangle {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for rectangle in Rectangle::all() {
+            let candidates = rectangle.corners.map(|corner| possible_values.of_cell(corner));
+
+            //  All corners must be unsolved, solved corners are the domain of avoidable rectangles.
+            if candidates.iter().any(|candidates| candidates.size() < 2) {
+                continue;
+            }
+
+            let common = candidates.iter().fold(DigitSet::full(), |common, candidates| common.intersection(candidates));
+
+            for pair in pairs(common) {
+                let mut analysis = RectangleAnalysis { possible_values, refinements, rectangle, candidates, pair };
+
+                if analysis.run() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct AvoidableRectangle {
+    givens: Grid,
+}
+
+impl AvoidableRectangle {
+    pub(super) fn new(givens: Grid) -> Self { Self { givens } }
+
+    //  Type 1: 3 corners are solved, the last one cannot complete the deadly pattern.
+    fn analyze_type1(
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        rectangle: &Rectangle,
+        solved: [Option<Digit>; 4],
+    )
+        -> bool
+    {
+        let Some(unsolved) = (0..4).find(|index| solved[*index].is_none()) else { return false };
+
+        let (Some(a), Some(b), Some(other)) =
+            (solved[Rectangle::opposite(unsolved)], solved[Rectangle::row_mate(unsolved)], solved[Rectangle::column_mate(unsolved)])
+        else {
+            return false;
+        };
+
+        if a == b || b != other {
+            return false;
+        }
+
+        let reason = RefinementReason::AvoidableRectangle(UniqueRectangleKind::Type1, rectangle.cells(), pair(a, b));
+
+        remove_possibility(possible_values, refinements, rectangle.corners[unsolved], a, &reason)
+    }
+
+    //  Type 2: 2 corners sharing a line are solved, the 2 others are missing a single extra digit to complete the
+    //  deadly pattern, hence this extra digit must be in either.
+    fn analyze_type2(
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+        rectangle: &Rectangle,
+        solved: [Option<Digit>; 4],
+    )
+        -> bool
+    {
+        for first in 0..4 {
+            for second in [Rectangle::row_mate(first), Rectangle::column_mate(first)] {
+                let (Some(a), Some(b)) = (solved[first], solved[second]) else { continue };
+
+                if a == b {
+                    continue;
+                }
+
+                let (first_opposite, second_opposite) = (Rectangle::opposite(first), Rectangle::opposite(second));
+
+                if solved[first_opposite].is_some() || solved[second_opposite].is_some() {
+                    continue;
+                }
+
+                let first_candidates = possible_values.of_cell(rectangle.corners[first_opposite]);
+                let second_candidates = possible_values.of_cell(rectangle.corners[second_opposite]);
+
+                if !first_candidates.has(a) || !second_candidates.has(b) {
+                    continue;
+                }
+
+                let first_extra = first_candidates.difference(&DigitSet::from(a));
+                let second_extra = second_candidates.difference(&DigitSet::from(b));
+
+                if first_extra.size() != 1 || first_extra != second_extra {
+                    continue;
+                }
+
+                let extra = first_extra.into_iter().next().expect("Single extra digit");
+
+                let targets = CellSet::peers(rectangle.corners[first_opposite])
+                    .intersection(&CellSet::peers(rectangle.corners[second_opposite]));
+
+                let reason = RefinementReason::AvoidableRectangle(UniqueRectangleKind::Type2, rectangle.cells(), pair(a, b));
+
+                let mut removed = false;
+
+                for target in targets {
+                    removed |= remove_possibility(possible_values, refinements, target, extra, &reason);
+                }
+
+                if removed {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl AnalysisImpl for AvoidableRectangle {
+    fn analyze_grid(
+        &mut self,
+        possible_values: &mut PossibleValues,
+        refinements: &JournalWriter<Refinement>,
+    )
+    {
+        for rectangle in Rectangle::all() {
+            //  Givens cannot be swapped, hence never form a deadly pattern.
+            if rectangle.corners.iter().any(|corner| self.givens.get_digit(*corner).is_some()) {
+                continue;
+            }
+
+            let solved = rectangle.corners.map(|corner| {
+                let candidates = possible_values.of_cell(corner);
+
+                if candidates.size() == 1 { candidates.into_iter().next() } else { None }
+            });
+
+            let removed = match solved.iter().filter(|digit| digit.is_some()).count() {
+                3 => Self::analyze_type1(possible_values, refinements, &rectangle, solved),
+                2 => Self::analyze_type2(possible_values, refinements, &rectangle, solved),
+                _ => false,
+            };
+
+            if removed {
+                return;
+            }
+        }
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  A rectangle spanning exactly 2 squares.
+#[derive(Clone, Copy, Debug)]
+struct Rectangle {
+    //  Corners, in order: top-left, top-right, bottom-left, bottom-right.
+    //
+    //  Hence for a given corner, the corner sharing its row is `index ^ 1`, the corner sharing its column is
+    //  `index ^ 2`, and the diagonally opposite corner is `index ^ 3`.
+    corners: [CellIndex; 4],
+}
+
+impl Rectangle {
+    //  Returns all the rectangles spanning exactly 2 squares.
+    fn all() -> Vec<Rectangle> {
+        let mut result = Vec::new();
+
+        for top in RowIndex::all() {
+            for bottom in RowIndex::all().skip(top.value() + 1) {
+                let same_band = top.value() / SQUARE_DIMENSION == bottom.value() / SQUARE_DIMENSION;
+
+                for left in ColumnIndex::all() {
+                    for right in ColumnIndex::all().skip(left.value() + 1) {
+                        let same_stack = left.value() / SQUARE_DIMENSION == right.value() / SQUARE_DIMENSION;
+
+                        if same_band == same_stack {
+                            continue;
+                        }
+
+                        let corners = [
+                            CellIndex::from_coordinates(top, left),
+                            CellIndex::from_coordinates(top, right),
+                            CellIndex::from_coordinates(bottom, left),
+                            CellIndex::from_coordinates(bottom, right),
+                        ];
+
+                        result.push(Rectangle { corners });
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn row_mate(index: usize) -> usize { index ^ 1 }
+
+    fn column_mate(index: usize) -> usize { index ^ 2 }
+
+    fn opposite(index: usize) -> usize { index ^ 3 }
+
+    fn cells(&self) -> CellSet { self.corners.into_iter().collect() }
+
+    //  Returns the groups shared by 2 corners.
+    fn shared_groups(&self, first: usize, second: usize) -> impl Iterator<Item = Group> {
+        let second = self.corners[second];
+
+        Group::groups(self.corners[first]).into_iter().filter(move |group| group.contains(second))
+    }
+}
+
+//  Analysis of a unique rectangle for a given pair of digits.
+struct RectangleAnalysis<'a> {
+    possible_values: &'a mut PossibleValues,
+    refinements: &'a JournalWriter<Refinement>,
+    rectangle: Rectangle,
+    candidates: [DigitSet; 4],
+    pair: DigitSet,
+}
+
+impl RectangleAnalysis<'_> {
+    //  Returns whether any possibility was removed.
+    fn run(&mut self) -> bool {
+        let roof: Vec<usize> = (0..4).filter(|index| self.candidates[*index] != self.pair).collect();
+
+        match roof.len() {
+            1 => self.analyze_type1(roof[0]),
+            2 => {
+                let (first, second) = (roof[0], roof[1]);
+
+                self.analyze_single_extra(&roof)
+                    || self.analyze_type4(first, second)
+                    || self.analyze_type6(first, second)
+                    || self.analyze_hidden()
+                    || self.analyze_type3(first, second)
+            },
+            3 => self.analyze_single_extra(&roof) || self.analyze_hidden(),
+            _ => false,
+        }
+    }
+
+    //  Type 1: a single corner has extra digits, hence cannot be either digit of the pair.
+    fn analyze_type1(&mut self, roof: usize) -> bool {
+        let mut removed = false;
+
+        for digit in self.pair {
+            removed |= self.remove(UniqueRectangleKind::Type1, self.rectangle.corners[roof], digit);
+        }
+
+        removed
+    }
+
+    //  Type 2 and 5: the corners with extra digits share a single extra digit, which must be in one of them.
+    fn analyze_single_extra(&mut self, roof: &[usize]) -> bool {
+        let extras = roof.iter()
+            .fold(DigitSet::default(), |extras, index| extras.union(&self.candidates[*index].difference(&self.pair)));
+
+        if extras.size() != 1 {
+            return false;
+        }
+
+        let extra = extras.into_iter().next().expect("Single extra digit");
+
+        let kind = if roof.len() == 2 && self.rectangle.shared_groups(roof[0], roof[1]).next().is_some() {
+            UniqueRectangleKind::Type2
+        } else {
+            UniqueRectangleKind::Type5
+        };
+
+        let targets = roof.iter()
+            .fold(CellSet::full(), |targets, index| targets.intersection(&CellSet::peers(self.rectangle.corners[*index])))
+            .difference(&self.rectangle.cells());
+
+        let mut removed = false;
+
+        for target in targets {
+            removed |= self.remove(kind, target, extra);
+        }
+
+        removed
+    }
+
+    //  Type 3: the extra digits of the roof act as a virtual cell, which may form a subset with other cells of a group
+    //  shared by the roof.
+    fn analyze_type3(&mut self, first: usize, second: usize) -> bool {
+        let extras = self.candidates[first].difference(&self.pair).union(&self.candidates[second].difference(&self.pair));
+
+        let groups: Vec<_> = self.rectangle.shared_groups(first, second).collect();
+
+        for group in groups {
+            let others: Vec<_> = group.cells()
+                .into_iter()
+                .filter(|cell| !self.rectangle.cells().has(*cell) && self.possible_values.of_cell(*cell).size() > 1)
+                .collect();
+
+            //  Enumerate the subsets of other cells, smallest first, leaving at least one cell out.
+            for size in 1..others.len() {
+                for mask in 0u32..(1 << others.len()) {
+                    if mask.count_ones() as usize != size {
+                        continue;
+                    }
+
+                    let subset: CellSet = select(&others, mask).collect();
+
+                    let digits = subset.into_iter()
+                        .fold(extras, |digits, cell| digits.union(&self.possible_values.of_cell(cell)));
+
+                    if digits.size() != size + 1 {
+                        continue;
+                    }
+
+                    let kind = UniqueRectangleKind::Type3(subset, digits);
+
+                    let mut removed = false;
+
+                    for cell in group.cells() {
+                        if subset.has(cell) || self.rectangle.cells().has(cell) {
+                            continue;
+                        }
+
+                        for digit in digits {
+                            removed |= self.remove(kind, cell, digit);
+                        }
+                    }
+
+                    if removed {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    //  Type 4: within a group shared by the roof, one digit of the pair is only possible in the roof, hence the other
+    //  digit of the pair cannot be in the roof.
+    fn analyze_type4(&mut self, first: usize, second: usize) -> bool {
+        let groups: Vec<_> = self.rectangle.shared_groups(first, second).collect();
+
+        for group in groups {
+            for digit in self.pair {
+                if self.possible_values.of_group(group).count(digit) != 2 {
+                    continue;
+                }
+
+                let other = self.other(digit);
+
+                let mut removed = false;
+
+                for index in [first, second] {
+                    removed |= self.remove(UniqueRectangleKind::Type4, self.rectangle.corners[index], other);
+                }
+
+                if removed {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    //  Type 6: the roof is diagonal, and one digit of the pair is only possible in the rectangle in both its rows and
+    //  both its columns, hence it must be in the floor.
+    fn analyze_type6(&mut self, first: usize, second: usize) -> bool {
+        if Rectangle::opposite(first) != second {
+            return false;
+        }
+
+        let corners = self.rectangle.corners;
+        let lines = [Group::row(corners[0]), Group::row(corners[3]), Group::column(corners[0]), Group::column(corners[3])];
+
+        for digit in self.pair {
+            if lines.iter().any(|line| self.possible_values.of_group(*line).count(digit) != 2) {
+                continue;
+            }
+
+            let mut removed = false;
+
+            for index in [first, second] {
+                removed |= self.remove(UniqueRectangleKind::Type6, corners[index], digit);
+            }
+
+            if removed {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //  Hidden: from a corner of the floor, one digit of the pair is only possible in the rectangle in both the row and
+    //  column of the opposite corner, hence the other digit of the pair cannot be in the opposite corner.
+    fn analyze_hidden(&mut self) -> bool {
+        let (candidates, pair) = (self.candidates, self.pair);
+
+        for floor in (0..4).filter(|index| candidates[*index] == pair) {
+            let opposite = self.rectangle.corners[Rectangle::opposite(floor)];
+
+            for digit in self.pair {
+                let row = self.possible_values.of_group(Group::row(opposite)).count(digit);
+                let column = self.possible_values.of_group(Group::column(opposite)).count(digit);
+
+                if row != 2 || column != 2 {
+                    continue;
+                }
+
+                if self.remove(UniqueRectangleKind::Hidden, opposite, self.other(digit)) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn other(&self, digit: Digit) -> Digit {
+        self.pair.difference(&DigitSet::from(digit)).into_iter().next().expect("Pair of digits")
+    }
+
+    fn remove(&mut self, kind: UniqueRectangleKind, cell: CellIndex, digit: Digit) -> bool {
+        let reason = RefinementReason::UniqueRectangle(kind, self.rectangle.cells(), self.pair);
+
+        remove_possibility(self.possible_values, self.refinements, cell, digit, &reason)
+    }
+}
+
+//  Returns all pairs of digits of the set.
+fn pairs(digits: DigitSet) -> impl Iterator<Item = DigitSet> {
+    digits.into_iter()
+        .flat_map(move |a| digits.into_iter().filter(move |b| *b > a).map(move |b| pair(a, b)))
+}
+
+fn pair(a: Digit, b: Digit) -> DigitSet { [a, b].into_iter().collect() }
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::solver::{Analysis, Analyzer, AnalyzerConfig};
+use crate::test_utils::{cell, digit, parse, parse_candidates};
+
+//  In each of the fixtures, the rectangle is r1c1, r1c4, r2c1 and r2c4, for the pair 1 and 2, and every other cell is
+//  solved to 9, save for the few cells an elimination targets or relies upon.
+
+//  r2c4 is the only corner with an extra digit.
+const TYPE1: &str = "
+    12 9 9 12 9 9 9 9 9
+    12 9 9 123 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r2c1 and r2c4 share the extra digit 3, also possible in r2c7.
+const TYPE2: &str = "
+    12 9 9 12 9 9 9 9 9
+    123 9 9 123 9 9 39 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r2c1 and r2c4 have the extra digits 3 and 4, forming a naked pair with r2c7 within r2, where r2c8 also holds 1 and
+//  2 so that neither digit of the pair is confined to the rectangle.
+const TYPE3: &str = "
+    12 9 9 12 9 9 9 9 9
+    123 9 9 124 9 9 34 1234 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r2c1 and r2c4 have distinct extra digits, and 1 is only possible in them within r2.
+const TYPE4: &str = "
+    12 9 9 12 9 9 9 9 9
+    123 9 9 124 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r1c4 and r2c1, diagonally opposite, share the extra digit 3, also possible in r1c2 which sees both.
+const TYPE5: &str = "
+    12 39 9 123 9 9 9 9 9
+    123 9 9 12 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r1c4 and r2c1, diagonally opposite, have distinct extra digits, and 1 is only possible in the rectangle within both
+//  its rows and both its columns.
+const TYPE6: &str = "
+    12 9 9 123 9 9 9 9 9
+    124 9 9 12 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  Only r1c1 is reduced to the pair, and 1 is only possible in the rectangle within both r2 and c4.
+const HIDDEN: &str = "
+    12 9 9 123 9 9 9 9 9
+    124 9 9 125 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r1c1, r1c4 and r2c1 are solved to 1, 2 and 2, r2c4 cannot be 1.
+const AVOIDABLE_TYPE1: &str = "
+    1 9 9 2 9 9 9 9 9
+    2 9 9 13 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+//  r1c1 and r1c4 are solved to 1 and 2, r2c4 and r2c1 share the extra digit 3, also possible in r2c7.
+const AVOIDABLE_TYPE2: &str = "
+    1 9 9 2 9 9 9 9 9
+    23 9 9 13 9 9 39 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+    9 9 9 9 9 9 9 9 9
+";
+
+#[test]
+fn unique_rectangle_type1() {
+    let reason = unique(UniqueRectangleKind::Type1);
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 4), digit(1), reason.clone()), Refinement::new(cell(2, 4), digit(2), reason)],
+        analyze_unique(TYPE1)
+    );
+}
+
+#[test]
+fn unique_rectangle_type2() {
+    let reason = unique(UniqueRectangleKind::Type2);
+
+    assert_eq!(vec![Refinement::new(cell(2, 7), digit(3), reason)], analyze_unique(TYPE2));
+}
+
+#[test]
+fn unique_rectangle_type3() {
+    let subset: CellSet = [cell(2, 7)].into_iter().collect();
+    let digits: DigitSet = [digit(3), digit(4)].into_iter().collect();
+
+    let reason = unique(UniqueRectangleKind::Type3(subset, digits));
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 8), digit(3), reason.clone()), Refinement::new(cell(2, 8), digit(4), reason)],
+        analyze_unique(TYPE3)
+    );
+}
+
+#[test]
+fn unique_rectangle_type4() {
+    let reason = unique(UniqueRectangleKind::Type4);
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 1), digit(2), reason.clone()), Refinement::new(cell(2, 4), digit(2), reason)],
+        analyze_unique(TYPE4)
+    );
+}
+
+#[test]
+fn unique_rectangle_type5() {
+    let reason = unique(UniqueRectangleKind::Type5);
+
+    assert_eq!(vec![Refinement::new(cell(1, 2), digit(3), reason)], analyze_unique(TYPE5));
+}
+
+#[test]
+fn unique_rectangle_type6() {
+    let reason = unique(UniqueRectangleKind::Type6);
+
+    assert_eq!(
+        vec![Refinement::new(cell(1, 4), digit(1), reason.clone()), Refinement::new(cell(2, 1), digit(1), reason)],
+        analyze_unique(TYPE6)
+    );
+}
+
+#[test]
+fn unique_rectangle_hidden() {
+    let reason = unique(UniqueRectangleKind::Hidden);
+
+    assert_eq!(vec![Refinement::new(cell(2, 4), digit(2), reason)], analyze_unique(HIDDEN));
+}
+
+#[test]
+fn avoidable_rectangle_type1() {
+    let reason = avoidable(UniqueRectangleKind::Type1);
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 4), digit(1), reason)],
+        analyze_avoidable(AVOIDABLE_TYPE1, Grid::default())
+    );
+}
+
+#[test]
+fn avoidable_rectangle_type2() {
+    let reason = avoidable(UniqueRectangleKind::Type2);
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 7), digit(3), reason)],
+        analyze_avoidable(AVOIDABLE_TYPE2, Grid::default())
+    );
+}
+
+#[test]
+fn avoidable_rectangle_with_given() {
+    //  Same solved corners, but r1c1 is a given rather than a placement: it cannot be swapped.
+    let givens = parse("1");
+
+    assert_eq!(Vec::<Refinement>::new(), analyze_avoidable(AVOIDABLE_TYPE1, givens));
+    assert_eq!(Vec::<Refinement>::new(), analyze_avoidable(AVOIDABLE_TYPE2, givens));
+}
+
+#[test]
+fn requires_uniqueness() {
+    let placements = JournalWriter::new();
+
+    let mut analyzer = Analyzer::new(placements.reader());
+    analyzer.configure(AnalyzerConfig::only(&[Analysis::UniqueRectangle]));
+
+    let possible_values = parse_candidates(TYPE1);
+
+    for cell in CellIndex::all() {
+        for digit in DigitSet::full().difference(&possible_values.of_cell(cell)) {
+            analyzer.eliminate(cell, digit);
+        }
+    }
+
+    let eliminations = analyzer.refinements().len();
+
+    while !analyzer.is_done() {
+        analyzer.analyze();
+    }
+
+    assert_eq!(eliminations, analyzer.refinements().len());
+
+    analyzer.assume_uniqueness(Grid::default());
+
+    while !analyzer.is_done() {
+        analyzer.analyze();
+    }
+
+    let reason = unique(UniqueRectangleKind::Type1);
+
+    assert_eq!(
+        vec![Refinement::new(cell(2, 4), digit(1), reason.clone()), Refinement::new(cell(2, 4), digit(2), reason)],
+        analyzer.refinements().get_events()[eliminations..]
+    );
+}
+
+fn analyze_unique(candidates: &str) -> Vec<Refinement> {
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    UniqueRectangle.analyze_grid(&mut possible_values, &refinements);
+
+    refinements.reader().get_events()
+}
+
+fn analyze_avoidable(candidates: &str, givens: Grid) -> Vec<Refinement> {
+    let mut possible_values = parse_candidates(candidates);
+    let refinements = JournalWriter::new();
+
+    AvoidableRectangle::new(givens).analyze_grid(&mut possible_values, &refinements);
+
+    refinements.reader().get_events()
+}
+
+fn unique(kind: UniqueRectangleKind) -> RefinementReason {
+    RefinementReason::UniqueRectangle(kind, corners(), pair(digit(1), digit(2)))
+}
+
+fn avoidable(kind: UniqueRectangleKind) -> RefinementReason {
+    RefinementReason::AvoidableRectangle(kind, corners(), pair(digit(1), digit(2)))
+}
+
+fn corners() -> CellSet { [cell(1, 1), cell(1, 4), cell(2, 1), cell(2, 4)].into_iter().collect() }
+
+}
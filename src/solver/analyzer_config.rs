@@ -0,0 +1,196 @@
+//! The configuration of the analyses: which to run, in which order, and within which limits.
+
+use crate::model::DIMENSION;
+use super::{ALL_ANALYSES, Analysis};
+
+/// The default maximum number of cells of a naked subset: any.
+pub const DEFAULT_MAX_SUBSET_SIZE: usize = DIMENSION - 1;
+
+/// The default maximum size of a fish: a Jellyfish.
+pub const DEFAULT_MAX_FISH_SIZE: usize = 4;
+
+/// The default maximum size of a Franken or Mutant fish, whose search space is much larger: a Swordfish.
+pub const DEFAULT_MAX_FRANKEN_FISH_SIZE: usize = 3;
+
+/// The default maximum number of fins of a Franken or Mutant fish.
+pub const DEFAULT_MAX_FRANKEN_FISH_FINS: usize = 3;
+
+/// The default maximum number of nodes of an X-Cycle, or Alternating Inference Chain.
+pub const DEFAULT_MAX_CHAIN_LENGTH: usize = 16;
+
+/// The default maximum number of placements propagated from a Nishio assumption.
+pub const DEFAULT_NISHIO_DEPTH: usize = 6;
+
+/// The default maximum number of Nishio assumptions tried per analysis of the grid.
+pub const DEFAULT_NISHIO_EFFORT: usize = 200;
+
+/// The default maximum number of branches of a forcing chain: candidates of a cell, or positions within a group.
+pub const DEFAULT_MAX_FORCING_BRANCHES: usize = 3;
+
+/// The default maximum nesting of guesses, the assumption included.
+pub const DEFAULT_MAX_GUESS_DEPTH: usize = 8;
+
+/// The configuration of the analyses.
+///
+/// By default, all analyses are enabled, in the order of `ALL_ANALYSES`, from cheap to expensive.
+///
+/// The CellExclusion analysis, which maintains the possible values as digits are placed, is always enabled, and
+/// always runs first.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AnalyzerConfig {
+    analyses: Vec<Analysis>,
+    max_subset_size: usize,
+    max_fish_size: usize,
+    max_franken_fish_size: usize,
+    max_franken_fish_fins: usize,
+    max_chain_length: usize,
+    nishio_depth: usize,
+    nishio_effort: usize,
+    max_forcing_branches: usize,
+    max_guess_depth: usize,
+}
+
+impl AnalyzerConfig {
+    /// Creates a configuration enabling only the specified analyses, in the specified order.
+    ///
+    /// Duplicates are ignored.
+    pub fn only(analyses: &[Analysis]) -> Self {
+        let mut result = Self::default();
+
+        result.set_analyses(analyses);
+
+        result
+    }
+
+    /// Returns the enabled analyses, in order.
+    pub fn analyses(&self) -> &[Analysis] { &self.analyses }
+
+    /// Returns whether the analysis is enabled.
+    pub fn is_enabled(&self, analysis: Analysis) -> bool { self.analyses.contains(&analysis) }
+
+    /// Enables only the specified analyses, in the specified order.
+    ///
+    /// Duplicates are ignored.
+    pub fn set_analyses(&mut self, analyses: &[Analysis]) {
+        self.analyses = vec![Analysis::CellExclusion];
+
+        for analysis in analyses {
+            self.enable(*analysis);
+        }
+    }
+
+    /// Enables the analysis, after all those enabled so far, if not already enabled.
+    pub fn enable(&mut self, analysis: Analysis) {
+        if !self.is_enabled(analysis) {
+            self.analyses.push(analysis);
+        }
+    }
+
+    /// Disables the analysis, unless it is CellExclusion.
+    pub fn disable(&mut self, analysis: Analysis) {
+        if analysis != Analysis::CellExclusion {
+            self.analyses.retain(|enabled| *enabled != analysis);
+        }
+    }
+
+    /// Returns the maximum number of cells of a naked subset, see `Analysis::GroupSubsetInclusion`.
+    pub fn max_subset_size(&self) -> usize { self.max_subset_size }
+
+    /// Sets the maximum number of cells of a naked subset.
+    pub fn set_max_subset_size(&mut self, size: usize) { self.max_subset_size = size; }
+
+    /// Returns the maximum size of a fish, see `Analysis::Fish` and `Analysis::FinnedFish`.
+    pub fn max_fish_size(&self) -> usize { self.max_fish_size }
+
+    /// Sets the maximum size of a fish.
+    pub fn set_max_fish_size(&mut self, size: usize) { self.max_fish_size = size; }
+
+    /// Returns the maximum size of a Franken or Mutant fish, see `Analysis::FrankenFish`.
+    pub fn max_franken_fish_size(&self) -> usize { self.max_franken_fish_size }
+
+    /// Sets the maximum size of a Franken or Mutant fish.
+    pub fn set_max_franken_fish_size(&mut self, size: usize) { self.max_franken_fish_size = size; }
+
+    /// Returns the maximum number of fins of a Franken or Mutant fish, see `Analysis::FrankenFish`.
+    pub fn max_franken_fish_fins(&self) -> usize { self.max_franken_fish_fins }
+
+    /// Sets the maximum number of fins of a Franken or Mutant fish.
+    pub fn set_max_franken_fish_fins(&mut self, fins: usize) { self.max_franken_fish_fins = fins; }
+
+    /// Returns the maximum number of nodes of a chain, see `Analysis::XCycle` and
+    /// `Analysis::AlternatingInferenceChain`.
+    pub fn max_chain_length(&self) -> usize { self.max_chain_length }
+
+    /// Sets the maximum number of nodes of a chain.
+    pub fn set_max_chain_length(&mut self, length: usize) { self.max_chain_length = length; }
+
+    /// Returns the maximum number of placements propagated from an assumption, see `Analysis::Nishio`.
+    pub fn nishio_depth(&self) -> usize { self.nishio_depth }
+
+    /// Sets the maximum number of placements propagated from a Nishio assumption.
+    pub fn set_nishio_depth(&mut self, depth: usize) { self.nishio_depth = depth; }
+
+    /// Returns the maximum number of assumptions tried per analysis of the grid, see `Analysis::Nishio`.
+    pub fn nishio_effort(&self) -> usize { self.nishio_effort }
+
+    /// Sets the maximum number of Nishio assumptions tried per analysis of the grid.
+    pub fn set_nishio_effort(&mut self, effort: usize) { self.nishio_effort = effort; }
+
+    /// Returns the maximum number of branches of a forcing chain, see `Analysis::ForcingChain`.
+    pub fn max_forcing_branches(&self) -> usize { self.max_forcing_branches }
+
+    /// Sets the maximum number of branches of a forcing chain.
+    pub fn set_max_forcing_branches(&mut self, branches: usize) { self.max_forcing_branches = branches; }
+
+    /// Returns the maximum nesting of guesses, the assumption included, see `Analysis::BowmansBingo`.
+    pub fn max_guess_depth(&self) -> usize { self.max_guess_depth }
+
+    /// Sets the maximum nesting of guesses.
+    pub fn set_max_guess_depth(&mut self, depth: usize) { self.max_guess_depth = depth; }
+}
+
+impl Default for AnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            analyses: ALL_ANALYSES.to_vec(),
+            max_subset_size: DEFAULT_MAX_SUBSET_SIZE,
+            max_fish_size: DEFAULT_MAX_FISH_SIZE,
+            max_franken_fish_size: DEFAULT_MAX_FRANKEN_FISH_SIZE,
+            max_franken_fish_fins: DEFAULT_MAX_FRANKEN_FISH_FINS,
+            max_chain_length: DEFAULT_MAX_CHAIN_LENGTH,
+            nishio_depth: DEFAULT_NISHIO_DEPTH,
+            nishio_effort: DEFAULT_NISHIO_EFFORT,
+            max_forcing_branches: DEFAULT_MAX_FORCING_BRANCHES,
+            max_guess_depth: DEFAULT_MAX_GUESS_DEPTH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+#[test]
+fn only() {
+    let config = AnalyzerConfig::only(&[Analysis::Fish, Analysis::GroupInclusion, Analysis::Fish]);
+
+    assert_eq!(&[Analysis::CellExclusion, Analysis::Fish, Analysis::GroupInclusion], config.analyses());
+}
+
+#[test]
+fn enable_disable() {
+    let mut config = AnalyzerConfig::only(&[]);
+
+    config.enable(Analysis::XCycle);
+    config.enable(Analysis::XCycle);
+    config.disable(Analysis::CellExclusion);
+
+    assert_eq!(&[Analysis::CellExclusion, Analysis::XCycle], config.analyses());
+
+    config.disable(Analysis::XCycle);
+
+    assert!(!config.is_enabled(Analysis::XCycle));
+}
+
+}
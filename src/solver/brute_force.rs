@@ -0,0 +1,149 @@
+//! Brute-force search of the solutions of a grid.
+//!
+//! The search is not didactic in the least, and is only used to establish facts about a grid, such as whether its
+//! solution is unique.
+
+use crate::model::{DIMENSION, CellIndex, Digit, DigitSet, Grid, Group};
+
+/// Searches for the solutions of the grid, stopping as soon as `limit` solutions have been found.
+///
+/// Returns the solutions found, hence at most `limit`, and none if the grid is inconsistent.
+///
+/// #   Algorithmic Complexity
+///
+/// Exponential (time) in the number of unknown cells in the worst case, though the search always branches on the most
+/// constrained cell, which keeps it fast on actual puzzles.
+pub fn find_solutions(grid: &Grid, limit: usize) -> Vec<Grid> {
+    let mut search = Search { grid: *grid, used: [DigitSet::default(); NUMBER_GROUPS], solutions: Vec::new(), limit };
+
+    for cell in CellIndex::all() {
+        if let Some(digit) = grid.get_digit(cell) {
+            if !search.is_possible(cell, digit) {
+                return Vec::new();
+            }
+
+            search.mark(cell, digit);
+        }
+    }
+
+    if limit > 0 {
+        search.run();
+    }
+
+    search.solutions
+}
+
+//
+//  Implementation Details
+//
+
+const NUMBER_GROUPS: usize = 3 * DIMENSION;
+
+struct Search {
+    grid: Grid,
+    //  Digits already used in each group, indexed by GroupIndex.
+    used: [DigitSet; NUMBER_GROUPS],
+    solutions: Vec<Grid>,
+    limit: usize,
+}
+
+impl Search {
+    fn run(&mut self) {
+        let Some((cell, candidates)) = self.most_constrained() else {
+            self.solutions.push(self.grid);
+            return;
+        };
+
+        for digit in candidates {
+            self.mark(cell, digit);
+            self.grid.set_digit(cell, Some(digit));
+
+            self.run();
+
+            self.grid.set_digit(cell, None);
+            self.unmark(cell, digit);
+
+            if self.solutions.len() >= self.limit {
+                return;
+            }
+        }
+    }
+
+    //  Returns the unknown cell with the fewest candidates, and its candidates, or None if all cells are known.
+    fn most_constrained(&self) -> Option<(CellIndex, DigitSet)> {
+        let mut result: Option<(CellIndex, DigitSet)> = None;
+
+        for cell in CellIndex::all() {
+            if self.grid.get_digit(cell).is_some() {
+                continue;
+            }
+
+            let candidates = self.candidates(cell);
+
+            if result.is_none_or(|(_, best)| candidates.size() < best.size()) {
+                result = Some((cell, candidates));
+
+                if candidates.size() <= 1 {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+
+    fn candidates(&self, cell: CellIndex) -> DigitSet {
+        Group::groups(cell)
+            .into_iter()
+            .fold(DigitSet::full(), |candidates, group| candidates.difference(&self.used[group.index().value()]))
+    }
+
+    fn is_possible(&self, cell: CellIndex, digit: Digit) -> bool {
+        Group::groups(cell).into_iter().all(|group| !self.used[group.index().value()].has(digit))
+    }
+
+    fn mark(&mut self, cell: CellIndex, digit: Digit) {
+        for group in Group::groups(cell) {
+            self.used[group.index().value()].add(digit);
+        }
+    }
+
+    fn unmark(&mut self, cell: CellIndex, digit: Digit) {
+        for group in Group::groups(cell) {
+            self.used[group.index().value()].remove(digit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::test_utils::parse;
+
+#[test]
+fn unique_solution() {
+    let grid = parse("003020600900305001001806400008102900700000008006708200002609500800203009005010300");
+
+    let solutions = find_solutions(&grid, 2);
+
+    assert_eq!(1, solutions.len());
+    assert_eq!(DIMENSION * DIMENSION, solutions[0].number_solved());
+}
+
+#[test]
+fn multiple_solutions() {
+    let solutions = find_solutions(&Grid::default(), 2);
+
+    assert_eq!(2, solutions.len());
+    assert_ne!(solutions[0], solutions[1]);
+}
+
+#[test]
+fn no_solution() {
+    let grid = parse("11");
+
+    assert!(find_solutions(&grid, 2).is_empty());
+}
+
+}
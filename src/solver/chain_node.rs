@@ -0,0 +1,26 @@
+//! A node of a chain: a digit, possible within one or several cells.
+
+use crate::model::{CellSet, Digit};
+
+/// A node of a chain, true when the digit is within one of its cells.
+///
+/// A grouped node covers the 2 or 3 cells of the overlap of a square and a line in which the digit is possible.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ChainNode {
+    digit: Digit,
+    cells: CellSet,
+}
+
+impl ChainNode {
+    /// Creates an instance.
+    pub fn new(digit: Digit, cells: CellSet) -> Self { Self { digit, cells, } }
+
+    /// Returns the digit of the node.
+    pub fn digit(&self) -> Digit { self.digit }
+
+    /// Returns the cells of the node.
+    pub fn cells(&self) -> CellSet { self.cells }
+
+    /// Returns whether the node is grouped, that is covers several cells.
+    pub fn is_grouped(&self) -> bool { self.cells.size() > 1 }
+}
@@ -0,0 +1,45 @@
+//! The trace of a forcing chain: the singles propagated from an assumption.
+
+use crate::model::{Candidate, CellIndex, Digit, Group};
+
+/// A branch of a forcing chain: an assumption, and the singles it led to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ForcingBranch {
+    assumption: Candidate,
+    steps: Vec<ForcingStep>,
+    contradiction: Option<ForcingContradiction>,
+}
+
+impl ForcingBranch {
+    /// Creates an instance.
+    pub fn new(assumption: Candidate, steps: Vec<ForcingStep>, contradiction: Option<ForcingContradiction>) -> Self {
+        Self { assumption, steps, contradiction, }
+    }
+
+    /// Returns the candidate assumed to be true.
+    pub fn assumption(&self) -> Candidate { self.assumption }
+
+    /// Returns the singles which followed from the assumption, in order.
+    pub fn steps(&self) -> &[ForcingStep] { &self.steps }
+
+    /// Returns the contradiction the assumption led to, if any.
+    pub fn contradiction(&self) -> Option<ForcingContradiction> { self.contradiction }
+}
+
+/// A single propagated from an assumption.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ForcingStep {
+    /// The candidate is the only one left within its cell.
+    NakedSingle(Candidate),
+    /// The candidate is the only position left for its digit within the group.
+    HiddenSingle(Candidate, Group),
+}
+
+/// The contradiction an assumption led to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ForcingContradiction {
+    /// No digit is possible within the cell any longer.
+    EmptyCell(CellIndex),
+    /// The digit is not possible anywhere within the group any longer.
+    MissingDigit(Digit, Group),
+}
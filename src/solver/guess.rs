@@ -0,0 +1,33 @@
+//! The trace of a guess: the trial and error performed once logic runs out.
+
+use super::ForcingBranch;
+
+/// A guess which failed: an assumption, the singles it led to, and, should those not reach a contradiction by
+/// themselves, the further guesses on the most constrained cell, every one of which failed in turn.
+///
+/// The number of further guesses grows exponentially with their depth, hence the depth is bounded, see
+/// `AnalyzerConfig::max_guess_depth`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Guess {
+    branch: ForcingBranch,
+    nested: Vec<Guess>,
+}
+
+impl Guess {
+    /// Creates an instance.
+    pub fn new(branch: ForcingBranch, nested: Vec<Guess>) -> Self { Self { branch, nested, } }
+
+    /// Returns the branch propagated from the assumption.
+    ///
+    /// The branch holds the contradiction reached, if any; otherwise the further guesses all failed.
+    pub fn branch(&self) -> &ForcingBranch { &self.branch }
+
+    /// Returns the further guesses, one per candidate of the most constrained cell left by the branch, in order.
+    ///
+    /// Empty if the branch reached a contradiction by itself.
+    pub fn nested(&self) -> &[Guess] { &self.nested }
+
+    /// Returns the depth of the guess: 1 if the branch reached a contradiction by itself, and otherwise 1 more than
+    /// the deepest of the further guesses.
+    pub fn depth(&self) -> usize { 1 + self.nested.iter().map(Guess::depth).max().unwrap_or(0) }
+}
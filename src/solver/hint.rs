@@ -0,0 +1,43 @@
+//! A hint: the next placement, and the refinements which justified it.
+
+use super::{Placement, Refinement};
+
+/// A hint: a placement, along with the refinements which justified it, grouped by technique.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Hint {
+    placement: Placement,
+    techniques: Vec<(&'static str, Vec<Refinement>)>,
+}
+
+impl Hint {
+    /// Creates an instance, grouping the refinements by the name of their technique.
+    ///
+    /// The techniques appear in the order they are first used, and the refinements in order within each.
+    pub fn new(placement: Placement, refinements: Vec<Refinement>) -> Self {
+        let mut techniques: Vec<(&'static str, Vec<Refinement>)> = Vec::new();
+
+        for refinement in refinements {
+            let name = refinement.reason().name();
+
+            match techniques.iter_mut().find(|(technique, _)| *technique == name) {
+                Some((_, group)) => group.push(refinement),
+                None => techniques.push((name, vec![refinement])),
+            }
+        }
+
+        Self { placement, techniques, }
+    }
+
+    /// Returns the placement.
+    pub fn placement(&self) -> &Placement { &self.placement }
+
+    /// Returns the refinements, grouped by the name of their technique.
+    ///
+    /// Empty if the placement follows from earlier refinements only, or was made by the user.
+    pub fn techniques(&self) -> &[(&'static str, Vec<Refinement>)] { &self.techniques }
+
+    /// Returns all the refinements, technique by technique.
+    pub fn refinements(&self) -> impl Iterator<Item = &Refinement> {
+        self.techniques.iter().flat_map(|(_, refinements)| refinements)
+    }
+}
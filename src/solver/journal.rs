@@ -1,24 +1,33 @@
 //! A generic journal facility.
+//!
+//! The events are shared between the writer and its readers, which may live on different threads: the readers only
+//! contend with the writer, and the writer only locks the journal to append, or truncate, events.
+//!
+//! Truncating the journal moves back any cursor beyond its new end, hence the events appended afterwards are handled
+//! in turn, rather than skipped.
 
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 /// A reader of the journal.
 ///
 /// The reader can only, well, read.
 #[derive(Debug)]
-pub struct JournalReader<T>(Rc<RefCell<Vec<T>>>);
+pub struct JournalReader<T>(Arc<RwLock<Events<T>>>);
 
 impl<T> JournalReader<T> {
     /// Returns the size of the journal, so far.
-    pub fn len(&self) -> usize { self.0.borrow().len() }
+    pub fn len(&self) -> usize { read(&self.0).events.len() }
+
+    /// Returns whether the journal is empty, so far.
+    pub fn is_empty(&self) -> bool { read(&self.0).events.is_empty() }
 }
 
 impl<T: Clone> JournalReader<T> {
     /// Returns a clone of the event at the specified index, if any.
-    pub fn get_event(&self, index: usize) -> Option<T> { self.0.borrow().get(index).cloned() }
+    pub fn get_event(&self, index: usize) -> Option<T> { read(&self.0).events.get(index).cloned() }
 
     /// Returns a clone of _all_ the events.
-    pub fn get_events(&self) -> Vec<T> { self.0.borrow().clone() }
+    pub fn get_events(&self) -> Vec<T> { read(&self.0).events.clone() }
 }
 
 impl<T> Clone for JournalReader<T> {
@@ -26,21 +35,38 @@ impl<T> Clone for JournalReader<T> {
 }
 
 /// Single cursor over a journal reader.
+///
+/// Should the journal be truncated below the position of the cursor, the cursor is moved back to its new end.
 #[derive(Debug)]
 pub struct JournalCursor<T> {
     reader: JournalReader<T>,
     cursor: usize,
+    //  The number of truncations of the journal accounted for in the cursor.
+    generation: usize,
 }
 
 impl<T> JournalCursor<T> {
     /// Creates an instance.
-    pub fn new(reader: JournalReader<T>) -> Self { Self { reader, cursor: 0, } }
+    pub fn new(reader: JournalReader<T>) -> Self {
+        let generation = reader.generation();
+
+        Self { reader, cursor: 0, generation, }
+    }
 
     /// Returns a handle to the reader.
     pub fn reader(&self) -> &JournalReader<T> { &self.reader }
 
     /// Returns whether the cursor has processed all elements so far.
-    pub fn is_done(&self) -> bool { self.cursor == self.reader.len() }
+    pub fn is_done(&self) -> bool { self.position() >= self.reader.len() }
+
+    /// Returns the position of the cursor, that is the number of events handled so far.
+    pub fn position(&self) -> usize { self.reader.clamp([self.cursor], self.generation).0[0] }
+
+    /// Moves the cursor to the specified position, as if exactly that many events had been handled.
+    pub fn seek(&mut self, position: usize) {
+        self.cursor = position;
+        self.generation = self.reader.generation();
+    }
 }
 
 impl<T: Clone> JournalCursor<T> {
@@ -58,7 +84,9 @@ impl<T: Clone> JournalCursor<T> {
     where
         H: FnOnce(T) -> R,
     {
-        let index = self.cursor;
+        let ([index], generation) = self.reader.clamp([self.cursor], self.generation);
+        (self.cursor, self.generation) = (index, generation);
+
         let event = self.reader.get_event(index)?;
 
         let result = handler(event);
@@ -70,25 +98,60 @@ impl<T: Clone> JournalCursor<T> {
 }
 
 impl<T> Clone for JournalCursor<T> {
-    fn clone(&self) -> Self { Self { reader: self.reader.clone(), cursor: self.cursor, } }
+    fn clone(&self) -> Self { Self { reader: self.reader.clone(), cursor: self.cursor, generation: self.generation, } }
 }
 
 /// Multiple cursors over a journal reader, for memory reasons.
+///
+/// Should the journal be truncated below the position of any cursor, the cursor is moved back to its new end.
 #[derive(Debug)]
 pub struct JournalMultiCursor<T, const N: usize> {
     reader: JournalReader<T>,
     cursors: [usize; N],
+    //  The number of truncations of the journal accounted for in the cursors.
+    generation: usize,
 }
 
 impl<T, const N: usize> JournalMultiCursor<T, N> {
     /// Creates an instance.
-    pub fn new(reader: JournalReader<T>) -> Self { Self { reader, cursors: [0; N], } }
+    pub fn new(reader: JournalReader<T>) -> Self {
+        let generation = reader.generation();
+
+        Self { reader, cursors: [0; N], generation, }
+    }
 
     /// Returns a handle to the reader.
     pub fn reader(&self) -> &JournalReader<T> { &self.reader }
 
     /// Returns whether the cursor has processed all elements so far.
-    pub fn is_done(&self, cursor: usize) -> bool { self.cursors[cursor] == self.reader.len() }
+    pub fn is_done(&self, cursor: usize) -> bool { self.positions()[cursor] >= self.reader.len() }
+
+    /// Returns the positions of all the cursors, that is the number of events handled so far by each.
+    pub fn positions(&self) -> [usize; N] { self.reader.clamp(self.cursors, self.generation).0 }
+
+    /// Moves all the cursors to the specified positions, as if exactly that many events had been handled by each.
+    pub fn seek(&mut self, positions: [usize; N]) {
+        self.cursors = positions;
+        self.generation = self.reader.generation();
+    }
+
+    /// Marks all events so far as handled for a specific cursor, without handling them.
+    ///
+    /// Returns the number of events skipped, possibly 0.
+    ///
+    /// #   Panics
+    ///
+    /// -   If the cursor index is greater than or equal to N.
+    pub fn catch_up(&mut self, cursor: usize) -> usize {
+        self.sync();
+
+        let len = self.reader.len();
+        let skipped = len.saturating_sub(self.cursors[cursor]);
+
+        self.cursors[cursor] = len;
+
+        skipped
+    }
 }
 
 impl<T: Clone, const N: usize> JournalMultiCursor<T, N> {
@@ -107,6 +170,8 @@ impl<T: Clone, const N: usize> JournalMultiCursor<T, N> {
     where
         H: FnOnce(T) -> R,
     {
+        self.sync();
+
         let index = self.cursors[cursor];
         let event = self.reader.get_event(index)?;
 
@@ -119,24 +184,55 @@ impl<T: Clone, const N: usize> JournalMultiCursor<T, N> {
 }
 
 impl<T, const N: usize> Clone for JournalMultiCursor<T, N> {
-    fn clone(&self) -> Self { Self { reader: self.reader.clone(), cursors: self.cursors, } }
+    fn clone(&self) -> Self {
+        Self { reader: self.reader.clone(), cursors: self.cursors, generation: self.generation, }
+    }
 }
 
 /// A writer to the journal.
 ///
 /// The writer can both read and append events.
 #[derive(Debug)]
-pub struct JournalWriter<T>(Rc<RefCell<Vec<T>>>);
+pub struct JournalWriter<T>(Arc<RwLock<Events<T>>>);
 
 impl<T> JournalWriter<T> {
     /// Creates a JournalWriter.
-    pub fn new() -> Self { Self(Rc::new(RefCell::default())) }
+    pub fn new() -> Self { Self(Arc::new(RwLock::default())) }
 
     /// Creates a JournalReader for the current writer.
     pub fn reader(&self) -> JournalReader<T> { JournalReader(self.0.clone()) }
 
     /// Appends an event to the journal.
-    pub fn append_event(&self, event: T) { self.0.borrow_mut().push(event) }
+    pub fn append_event(&self, event: T) { write(&self.0).events.push(event) }
+
+    /// Appends several events to the journal, in order.
+    pub fn append_events(&self, events: Vec<T>) { write(&self.0).events.extend(events) }
+
+    /// Truncates the journal to its first `len` events.
+    ///
+    /// The cursors beyond `len` are moved back to `len`.
+    ///
+    /// Returns the events removed, in order, possibly none.
+    pub fn truncate(&self, len: usize) -> Vec<T> {
+        let mut events = write(&self.0);
+
+        if len >= events.events.len() {
+            return Vec::new();
+        }
+
+        events.generation += 1;
+
+        //  A cursor accounting for the truncations up to any of the latest ones with a greater or equal length is moved
+        //  back to `len` at most, hence those are superseded.
+        while events.truncations.last().is_some_and(|(_, truncated)| *truncated >= len) {
+            events.truncations.pop();
+        }
+
+        let generation = events.generation;
+        events.truncations.push((generation, len));
+
+        events.events.split_off(len)
+    }
 }
 
 impl<T> Clone for JournalWriter<T> {
@@ -146,3 +242,118 @@ impl<T> Clone for JournalWriter<T> {
 impl<T> Default for JournalWriter<T> {
     fn default() -> Self { Self::new() }
 }
+
+impl<T> From<Vec<T>> for JournalWriter<T> {
+    fn from(events: Vec<T>) -> Self {
+        Self(Arc::new(RwLock::new(Events { events, generation: 0, truncations: Vec::new(), })))
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  The events of the journal, and its truncations.
+#[derive(Debug)]
+struct Events<T> {
+    events: Vec<T>,
+    //  The number of truncations so far.
+    generation: usize,
+    //  The generation and length of the truncations which may still move a cursor back, by increasing length: the
+    //  shortest length since a given generation is the first one after it.
+    truncations: Vec<(usize, usize)>,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self { Self { events: Vec::new(), generation: 0, truncations: Vec::new(), } }
+}
+
+impl<T> JournalReader<T> {
+    //  Returns the number of truncations so far.
+    fn generation(&self) -> usize { read(&self.0).generation }
+
+    //  Returns the positions, as of the specified generation, moved back by the truncations since, along with the
+    //  current generation.
+    fn clamp<const N: usize>(&self, positions: [usize; N], generation: usize) -> ([usize; N], usize) {
+        let events = read(&self.0);
+
+        let shortest = events.truncations.iter().find(|(truncation, _)| *truncation > generation).map(|(_, len)| *len);
+        let positions = positions.map(|position| shortest.map_or(position, |len| position.min(len)));
+
+        (positions, events.generation)
+    }
+}
+
+impl<T, const N: usize> JournalMultiCursor<T, N> {
+    //  Moves back the cursors, as per the truncations of the journal since they were last accounted for.
+    fn sync(&mut self) { (self.cursors, self.generation) = self.reader.clamp(self.cursors, self.generation); }
+}
+
+//  The events are only ever appended, or truncated, as a whole: a panic while holding the lock cannot leave them in an
+//  inconsistent state, hence poisoning is ignored.
+fn read<T>(events: &RwLock<Events<T>>) -> RwLockReadGuard<'_, Events<T>> {
+    events.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(events: &RwLock<Events<T>>) -> RwLockWriteGuard<'_, Events<T>> {
+    events.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+
+#[test]
+fn cursor_beyond_truncation() {
+    let writer = JournalWriter::from(vec![1, 2, 3]);
+
+    let mut cursor = JournalCursor::new(writer.reader());
+    while cursor.handle_next(|_| ()).is_some() {}
+
+    let mut multi: JournalMultiCursor<_, 1> = JournalMultiCursor::new(writer.reader());
+    multi.catch_up(0);
+
+    assert_eq!(vec![2, 3], writer.truncate(1));
+
+    assert!(cursor.is_done());
+    assert_eq!(None, cursor.handle_next(|event| event));
+
+    assert!(multi.is_done(0));
+    assert_eq!(0, multi.catch_up(0));
+    assert_eq!(None, multi.handle_next(0, |event| event));
+}
+
+
+#[test]
+fn cursor_after_truncation_and_append() {
+    let writer = JournalWriter::from(vec![1, 2, 3]);
+
+    let mut cursor = JournalCursor::new(writer.reader());
+    while cursor.handle_next(|_| ()).is_some() {}
+
+    let mut multi: JournalMultiCursor<_, 2> = JournalMultiCursor::new(writer.reader());
+    multi.catch_up(0);
+    multi.handle_next(1, |_| ());
+
+    //  Truncated below the cursors, then regrown beyond them.
+    writer.truncate(2);
+    writer.truncate(1);
+    writer.append_events(vec![4, 5, 6]);
+
+    assert_eq!(1, cursor.position());
+    assert_eq!([1, 1], multi.positions());
+
+    assert_eq!(Some(4), cursor.handle_next(|event| event));
+    assert_eq!(Some(4), multi.handle_next(0, |event| event));
+    assert_eq!(Some(4), multi.handle_next(1, |event| event));
+
+    //  Truncated above the cursors, which are left as they were.
+    writer.truncate(3);
+
+    assert_eq!(2, cursor.position());
+    assert_eq!(Some(5), cursor.handle_next(|event| event));
+    assert_eq!(1, multi.catch_up(1));
+}
+
+}
@@ -0,0 +1,70 @@
+//! Mistakes: moves of the user contradicting the known solution, though allowed by the rules of Sudoku.
+
+use std::{error, fmt};
+
+use crate::model::{CellIndex, Digit};
+use super::ForcingBranch;
+
+/// How mistakes are handled, once the solution is known.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MistakeMode {
+    /// A mistake is rejected as soon as made.
+    #[default]
+    Strict,
+    /// A mistake is accepted, and only reported once proven by logic: propagating singles from the digit placed, or
+    /// from each other candidate of the cell for an elimination, leads to a contradiction.
+    Lenient,
+}
+
+/// A move of the user contradicting the known solution: the placement of another digit, or the elimination of the
+/// digit of the solution.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Mistake {
+    cell: CellIndex,
+    digit: Digit,
+    //  Whether the digit was placed, or otherwise eliminated.
+    placement: bool,
+    proof: Vec<ForcingBranch>,
+}
+
+impl Mistake {
+    /// Creates an instance.
+    pub fn new(cell: CellIndex, digit: Digit, placement: bool, proof: Vec<ForcingBranch>) -> Self {
+        Self { cell, digit, placement, proof, }
+    }
+
+    /// Returns the cell of the move.
+    pub fn cell(&self) -> CellIndex { self.cell }
+
+    /// Returns the digit placed, or eliminated.
+    pub fn digit(&self) -> Digit { self.digit }
+
+    /// Returns whether the digit was placed, or otherwise eliminated.
+    pub fn is_placement(&self) -> bool { self.placement }
+
+    /// Returns the branches proving the mistake, each reaching a contradiction.
+    ///
+    /// For a placement, the single branch assuming the digit; for an elimination, one branch per other candidate of
+    /// the cell. Empty if the mistake was not proven, in strict mode.
+    pub fn proof(&self) -> &[ForcingBranch] { &self.proof }
+}
+
+impl fmt::Display for Mistake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let (row, column) = (self.cell.row(), self.cell.column());
+
+        if self.placement {
+            write!(f, "Placing {:?} at ({row:?}, {column:?}) is a mistake", self.digit)?;
+        } else {
+            write!(f, "Eliminating {:?} from ({row:?}, {column:?}) is a mistake, for it is the solution", self.digit)?;
+        }
+
+        if !self.proof.is_empty() {
+            write!(f, ", as proven by {} contradiction(s)", self.proof.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for Mistake {}
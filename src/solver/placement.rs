@@ -1,21 +1,44 @@
 //! The placement of a digit in a cell, taking a firm step towards completion.
 
+use std::{hash::{Hash, Hasher}, sync::Arc};
+
 use crate::model::{CellIndex, Digit};
+use super::Premise;
 
 /// Placement, and its meta-information.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+///
+/// The premises are not part of its identity: a placement equals any other of the same digit in the same cell, premises
+/// or not. They are shared, hence a placement is cheap to clone, though not Copy.
+#[derive(Clone, Debug)]
 pub struct Placement {
     cell: CellIndex,
     digit: Digit,
+    premises: Arc<[Premise]>,
 }
 
 impl Placement {
-    /// Creates an instance.
-    pub fn new(cell: CellIndex, digit: Digit) -> Self { Self { cell, digit, } }
+    /// Creates an instance, without premises, as for a given or a digit set by the user.
+    pub fn new(cell: CellIndex, digit: Digit) -> Self { Self { cell, digit, premises: Arc::new([]), } }
+
+    /// Returns the instance, with the specified premises.
+    pub fn with_premises(self, premises: Arc<[Premise]>) -> Self { Self { premises, ..self } }
 
     /// Returns the cell in which a digit was placed.
     pub fn cell(&self) -> CellIndex { self.cell }
 
     /// Returns the digit placed in the cell.
     pub fn digit(&self) -> Digit { self.digit }
+
+    /// Returns the earlier refinements the placement depends upon: the removals of all the other digits of its cell.
+    pub fn premises(&self) -> &[Premise] { &self.premises }
+}
+
+impl PartialEq for Placement {
+    fn eq(&self, other: &Self) -> bool { (self.cell, self.digit) == (other.cell, other.digit) }
+}
+
+impl Eq for Placement {}
+
+impl Hash for Placement {
+    fn hash<H: Hasher>(&self, state: &mut H) { (self.cell, self.digit).hash(state) }
 }
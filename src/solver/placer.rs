@@ -2,13 +2,18 @@
 
 use crate::model::{CellIndex, Digit};
 use super::{JournalCursor, JournalReader, JournalWriter, Placement, PossibleValues, Refinement};
+use super::provenance::PremiseTracker;
 
 /// Incremental digit placer.
-#[derive(Clone, Debug)]
+///
+/// The Placer is not Clone, as its journal of placements is shared with its readers: see `fork` instead.
+#[derive(Debug)]
 pub struct Placer {
     possible_values: PossibleValues,
     placements: JournalWriter<Placement>,
     refinements: JournalCursor<Refinement>,
+    //  Tracker of the premises of the placements.
+    tracker: PremiseTracker,
 }
 
 impl Placer {
@@ -17,12 +22,44 @@ impl Placer {
         let possible_values = PossibleValues::all();
         let refinements = JournalCursor::new(refinements);
 
-        Self { possible_values, placements, refinements, }
+        Self { possible_values, placements, refinements, tracker: PremiseTracker::default(), }
+    }
+
+    /// Creates an independent copy, appending to the specified placements, a copy of the placements so far, and over
+    /// the specified refinements.
+    pub fn fork(&self, placements: JournalWriter<Placement>, refinements: JournalReader<Refinement>) -> Self {
+        let mut refinements = JournalCursor::new(refinements);
+        refinements.seek(self.refinements.position());
+
+        Self { possible_values: self.possible_values, placements, refinements, tracker: self.tracker.clone(), }
     }
 
     /// Returns a handle over the placements.
     pub fn placements(&self) -> JournalReader<Placement> { self.placements.reader() }
 
+    /// Returns a snapshot of the current state, to restore it later on.
+    pub fn snapshot(&self) -> PlacerSnapshot {
+        PlacerSnapshot {
+            possible_values: self.possible_values,
+            placements: self.placements.reader().len(),
+            refinements: self.refinements.position(),
+        }
+    }
+
+    /// Restores a snapshot, truncating the placements to their length at the time.
+    ///
+    /// Returns the placements removed, to be appended anew should the snapshot be redone.
+    pub fn restore(&mut self, snapshot: &PlacerSnapshot) -> Vec<Placement> {
+        self.possible_values = snapshot.possible_values;
+        self.refinements.seek(snapshot.refinements);
+        self.tracker.reset();
+
+        self.placements.truncate(snapshot.placements)
+    }
+
+    /// Appends placements anew, as returned by `restore`, prior to restoring a later snapshot.
+    pub fn reappend(&mut self, placements: Vec<Placement>) { self.placements.append_events(placements); }
+
     /// Returns whether the Placer is done with placements so far.
     pub fn is_done(&self) -> bool { self.refinements.is_done() }
 
@@ -38,10 +75,13 @@ impl Placer {
 
     /// Incrementally process the next refinement.
     ///
-    /// Returns the placement, if any was made.
+    /// Returns the placement, if any was made; none is made should the refinement remove the last possibility of its
+    /// cell.
     pub fn handle_next_refinement(&mut self) -> Option<Placement> {
         let possible_values = &mut self.possible_values;
         let placements = &self.placements;
+        let tracker = &mut self.tracker;
+        let refinements = self.refinements.reader().clone();
 
         self.refinements.handle_next(|refinement| {
             let cell = refinement.cell();
@@ -57,16 +97,27 @@ impl Placer {
                 return None;
             }
 
-            //  The refinement has excluded all other possibilities for the given cell.
-            let digit = possible_values.of_cell(cell).into_iter().next().unwrap();
+            //  The refinement has excluded all other possibilities for the given cell, or all of them, should the moves
+            //  of the user contradict one another: then there is nothing to place.
+            let digit = possible_values.of_cell(cell).into_iter().next()?;
 
             #[cfg(debug_assertions)]
             eprintln!("Placer::handle_next_refinement - Place {digit:?} in {cell:?} ({:?}/{:?})", cell.row(), cell.column());
 
-            let placement = Placement::new(cell, digit);
-            placements.append_event(placement);
+            let placement = tracker.annotate_placement(Placement::new(cell, digit), &refinements, &placements.reader());
+            placements.append_event(placement.clone());
 
             Some(placement)
         }).flatten()
     }
 }
+
+/// A snapshot of the state of the Placer.
+#[derive(Clone, Debug)]
+pub struct PlacerSnapshot {
+    possible_values: PossibleValues,
+    //  Number of placements.
+    placements: usize,
+    //  Position of the cursor over refinements.
+    refinements: usize,
+}
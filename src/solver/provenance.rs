@@ -0,0 +1,384 @@
+//! The provenance of deductions: the earlier placements and refinements each depends upon.
+//!
+//! A refinement depends upon the removals of the candidates whose absence its reason relies on, within the scope of
+//! its pattern, and upon the placement which triggered it, if any. A placement made by the solver depends upon the
+//! removals of all the other digits of its cell.
+//!
+//! The premises are recorded with each entry of the journals, forming a dependency DAG over the journals.
+
+use std::{collections::{HashMap, HashSet}, sync::Arc};
+
+use crate::model::{Candidate, CandidateSet, CellIndex, CellSet, Digit, DigitSet, Group, GroupIndex, GroupSet};
+use super::{
+    ChainKind, ChainNode, ForcingBranch, ForcingChainKind, ForcingContradiction, ForcingStep, Guess, JournalReader,
+    Placement, PossibleValues, Refinement, RefinementReason,
+};
+
+/// An entry of the journals: the index of a placement, or of a refinement.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Premise {
+    /// The index of a placement, within the placements journal.
+    Placement(usize),
+    /// The index of a refinement, within the refinements journal.
+    Refinement(usize),
+}
+
+/// A step of an explanation: an entry of the journals, with its index.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Step {
+    /// A placement, and its index within the placements journal.
+    Placement(usize, Placement),
+    /// A refinement, and its index within the refinements journal.
+    Refinement(usize, Box<Refinement>),
+}
+
+impl Step {
+    /// Returns the entry of the journals this step corresponds to.
+    pub fn premise(&self) -> Premise {
+        match self {
+            Step::Placement(index, _) => Premise::Placement(*index),
+            Step::Refinement(index, _) => Premise::Refinement(*index),
+        }
+    }
+}
+
+//  Explains the targets, returning the steps they transitively depend upon, themselves included.
+//
+//  Each step appears after all of its premises, and the targets in order, each as early as its premises allow. The
+//  dependencies may run thousands deep, hence an explicit stack is used rather than recursion.
+pub(super) fn explain(placements: &[Placement], refinements: &[Refinement], targets: &[Premise]) -> Vec<Step> {
+    explain_anew(placements, refinements, targets, &mut HashSet::new())
+}
+
+//  Explains the targets, as `explain`, skipping the entries already explained, and recording those explained anew.
+pub(super) fn explain_anew(
+    placements: &[Placement],
+    refinements: &[Refinement],
+    targets: &[Premise],
+    explained: &mut HashSet<Premise>,
+)
+    -> Vec<Step>
+{
+    let mut result = Vec::new();
+
+    //  Each entry is pushed twice: first to expand its premises, then to emit it.
+    let mut stack: Vec<(Premise, bool)> = targets.iter().rev().map(|target| (*target, false)).collect();
+
+    while let Some((premise, expanded)) = stack.pop() {
+        if expanded {
+            result.push(match premise {
+                Premise::Placement(index) => Step::Placement(index, placements[index].clone()),
+                Premise::Refinement(index) => Step::Refinement(index, Box::new(refinements[index].clone())),
+            });
+
+            continue;
+        }
+
+        if !explained.insert(premise) {
+            continue;
+        }
+
+        let premises = match premise {
+            Premise::Placement(index) => placements[index].premises(),
+            Premise::Refinement(index) => refinements[index].premises(),
+        };
+
+        stack.push((premise, true));
+        stack.extend(premises.iter().rev().map(|premise| (*premise, false)));
+    }
+
+    result
+}
+
+//  Returns the entries which directly depend upon the specified one, placements first, in journal order.
+pub(super) fn dependents(placements: &[Placement], refinements: &[Refinement], premise: Premise) -> Vec<Premise> {
+    let placements = placements.iter()
+        .enumerate()
+        .filter(|(_, placement)| placement.premises().contains(&premise))
+        .map(|(index, _)| Premise::Placement(index));
+
+    let refinements = refinements.iter()
+        .enumerate()
+        .filter(|(_, refinement)| refinement.premises().contains(&premise))
+        .map(|(index, _)| Premise::Refinement(index));
+
+    placements.chain(refinements).collect()
+}
+
+//  Tracks the entries of the journals, to record the premises of each new placement or refinement.
+#[derive(Clone, Debug, Default)]
+pub(super) struct PremiseTracker {
+    //  The number of refinements tracked.
+    refinements: usize,
+    //  The number of placements tracked.
+    placements: usize,
+    //  The refinement which removed each candidate.
+    removed_by: HashMap<Candidate, usize>,
+    //  The placement within each cell.
+    placed_by: HashMap<CellIndex, usize>,
+}
+
+impl PremiseTracker {
+    //  Forgets all entries tracked, to track the journals anew after they were truncated.
+    pub(super) fn reset(&mut self) { *self = Self::default(); }
+
+    //  Returns the refinements, about to be appended to the journal, with their premises.
+    //
+    //  The possible values are those prior to the refinements, so that none is the premise of another.
+    pub(super) fn annotate(
+        &mut self,
+        batch: Vec<Refinement>,
+        possible_values: &PossibleValues,
+        refinements: &JournalReader<Refinement>,
+        placements: &JournalReader<Placement>,
+    )
+        -> Vec<Refinement>
+    {
+        self.track(refinements, placements);
+
+        batch.into_iter()
+            .map(|refinement| {
+                let premises = self.premises(&refinement, possible_values);
+
+                refinement.with_premises(premises)
+            })
+            .collect()
+    }
+
+    //  Returns the placement, about to be appended to the journal, with its premises: the removals of all the other
+    //  digits of its cell, in order.
+    pub(super) fn annotate_placement(
+        &mut self,
+        placement: Placement,
+        refinements: &JournalReader<Refinement>,
+        placements: &JournalReader<Placement>,
+    )
+        -> Placement
+    {
+        self.track(refinements, placements);
+
+        let cell = placement.cell();
+
+        let mut premises: Vec<Premise> = DigitSet::full()
+            .difference(&DigitSet::from(placement.digit()))
+            .into_iter()
+            .filter_map(|other| self.removed_by.get(&Candidate::new(cell, other)))
+            .map(|index| Premise::Refinement(*index))
+            .collect();
+
+        premises.sort();
+
+        placement.with_premises(premises.into())
+    }
+}
+
+//  Returns the candidates whose absence the reason of the refinement may rely on, given the possible values prior to
+//  the refinement.
+fn scope(refinement: &Refinement, possible_values: &PossibleValues) -> CandidateSet {
+    let (cell, digit) = (refinement.cell(), refinement.removed());
+
+    match refinement.reason() {
+        RefinementReason::CellExclusion(_) | RefinementReason::GroupExclusion(..) | RefinementReason::User => {
+            CandidateSet::empty()
+        },
+        RefinementReason::GroupInclusion(digit, group) => {
+            let mut others = CellSet::from(*group);
+            others.remove(cell);
+
+            of_digit(others, *digit)
+        },
+        RefinementReason::GroupOverlap(includer, overlapping) => {
+            of_digit(CellSet::from(*includer).difference(&CellSet::from(*overlapping)), digit)
+        },
+        RefinementReason::GroupSubsetInclusion(cells, digits, _) => {
+            let others: Vec<Digit> = DigitSet::full().into_iter().filter(|other| !digits.has(*other)).collect();
+
+            others.into_iter().map(|other| of_digit(*cells, other)).fold(CandidateSet::empty(), |a, b| a.union(&b))
+        },
+        RefinementReason::Fish(base, cover, fins) => {
+            of_digit(cells_of_groups(*base).difference(&cells_of_groups(*cover)).difference(fins), digit)
+        },
+        RefinementReason::XCycle(kind, nodes) | RefinementReason::AlternatingInferenceChain(kind, nodes) => {
+            chain_scope(possible_values, *kind, nodes)
+        },
+        RefinementReason::UniqueRectangle(_, corners, _) | RefinementReason::AvoidableRectangle(_, corners, _) => {
+            let groups: GroupSet = GroupIndex::all()
+                .map(Group::new)
+                .filter(|group| CellSet::from(*group).intersection(corners).size() >= 2)
+                .collect();
+
+            all_of(cells_of_groups(groups))
+        },
+        RefinementReason::SueDeCoq(cells, line, square) => all_of(cells.union(&line.cells()).union(&square.cells())),
+        RefinementReason::AlmostLockedSetXZ(a, b, _) => all_of(a.cells().union(&b.cells())),
+        RefinementReason::AlmostLockedSetXYWing(a, b, c) => all_of(a.cells().union(&b.cells()).union(&c.cells())),
+        RefinementReason::AlmostLockedSetChain(sets, _) => {
+            all_of(sets.iter().fold(CellSet::empty(), |cells, set| cells.union(&set.cells())))
+        },
+        RefinementReason::DeathBlossom(stem, petals) => {
+            all_of(petals.iter().fold(CellSet::from(*stem), |cells, (_, set)| cells.union(&set.cells())))
+        },
+        RefinementReason::Exocet(base, targets, cross_lines) => {
+            all_of(base.union(targets).union(&cells_of_groups(*cross_lines)))
+        },
+        RefinementReason::Medusa(_, first, second) => medusa_scope(&first.union(second)),
+        RefinementReason::Nishio(..) => of_digit(CellSet::full(), digit),
+        RefinementReason::PatternOverlay(digit, _) => of_digit(CellSet::full(), *digit),
+        RefinementReason::BivalueUniversalGrave(_) => {
+            all_of(CellIndex::all().filter(|cell| possible_values.of_cell(*cell).size() > 1).collect())
+        },
+        RefinementReason::ForcingChain(kind, branches) => {
+            let assumed = match kind {
+                ForcingChainKind::Contradiction => CandidateSet::empty(),
+                ForcingChainKind::Cell(cell) => all_of(CellSet::from(*cell)),
+                ForcingChainKind::Group(digit, group) => of_digit(CellSet::from(*group), *digit),
+            };
+
+            branches.iter().fold(assumed, |scope, branch| scope.union(&branch_scope(branch)))
+        },
+        RefinementReason::BowmansBingo(guess) => guess_scope(guess),
+    }
+}
+
+//  Returns the cell of the placement which triggered the refinement, if any.
+fn trigger(refinement: &Refinement) -> Option<CellIndex> {
+    match refinement.reason() {
+        RefinementReason::CellExclusion(_) => Some(refinement.cell()),
+        RefinementReason::GroupExclusion(cell, _) => Some(*cell),
+        _ => None,
+    }
+}
+
+//
+//  Implementation Details
+//
+
+impl PremiseTracker {
+    fn track(&mut self, refinements: &JournalReader<Refinement>, placements: &JournalReader<Placement>) {
+        for index in self.refinements..refinements.len() {
+            let refinement = refinements.get_event(index).expect("Within bounds");
+
+            self.removed_by.entry(Candidate::new(refinement.cell(), refinement.removed())).or_insert(index);
+        }
+
+        for index in self.placements..placements.len() {
+            let placement = placements.get_event(index).expect("Within bounds");
+
+            self.placed_by.entry(placement.cell()).or_insert(index);
+        }
+
+        self.refinements = refinements.len();
+        self.placements = placements.len();
+    }
+
+    fn premises(&self, refinement: &Refinement, possible_values: &PossibleValues) -> Arc<[Premise]> {
+        let trigger = trigger(refinement)
+            .and_then(|cell| self.placed_by.get(&cell))
+            .map(|placement| Premise::Placement(*placement));
+
+        let mut removals: Vec<Premise> = scope(refinement, possible_values)
+            .into_iter()
+            .filter(|candidate| !possible_values.of_cell(candidate.cell()).has(candidate.digit()))
+            .filter_map(|candidate| self.removed_by.get(&candidate))
+            .map(|removal| Premise::Refinement(*removal))
+            .collect();
+
+        removals.sort();
+
+        trigger.into_iter().chain(removals).collect()
+    }
+}
+
+//  Returns the candidates whose absence makes the links of the chain strong.
+fn chain_scope(possible_values: &PossibleValues, kind: ChainKind, nodes: &[ChainNode]) -> CandidateSet {
+    let closing = (kind == ChainKind::ContinuousLoop).then(|| (nodes[nodes.len() - 1], nodes[0]));
+
+    nodes.windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .chain(closing)
+        .map(|(first, second)| link_scope(possible_values, first, second))
+        .fold(CandidateSet::empty(), |a, b| a.union(&b))
+}
+
+//  Returns the candidates whose absence makes the link strong, if it is.
+fn link_scope(possible_values: &PossibleValues, first: ChainNode, second: ChainNode) -> CandidateSet {
+    if first.digit() != second.digit() {
+        let cell = first.cells().into_iter().next().expect("At least one cell");
+        let digits = possible_values.of_cell(cell);
+
+        if digits.size() != 2 {
+            return CandidateSet::empty();
+        }
+
+        return DigitSet::full().into_iter()
+            .filter(|digit| !digits.has(*digit))
+            .map(|digit| Candidate::new(cell, digit))
+            .collect();
+    }
+
+    let (digit, cells) = (first.digit(), first.cells().union(&second.cells()));
+
+    GroupIndex::all()
+        .map(Group::new)
+        .map(CellSet::from)
+        .filter(|group| cells.is_subset_of(group))
+        .find(|group| group.into_iter().all(|cell| cells.has(cell) || !possible_values.of_cell(cell).has(digit)))
+        .map_or_else(CandidateSet::empty, |group| of_digit(group.difference(&cells), digit))
+}
+
+//  Returns the candidates whose absence forms the links of the cluster: within the cells of the cluster, and within
+//  the groups holding several candidates of the cluster for a given digit.
+fn medusa_scope(cluster: &CandidateSet) -> CandidateSet {
+    let mut result = all_of(cluster.into_iter().map(|candidate| candidate.cell()).collect());
+
+    for digit in DigitSet::full().into_iter() {
+        let cells = cluster.of_digit(digit);
+
+        for group in GroupIndex::all().map(Group::new).map(CellSet::from) {
+            if group.intersection(&cells).size() >= 2 {
+                result = result.union(&of_digit(group, digit));
+            }
+        }
+    }
+
+    result
+}
+
+//  Returns the candidates whose absence the singles of the branch, and its contradiction if any, rely on.
+fn branch_scope(branch: &ForcingBranch) -> CandidateSet {
+    let singles = branch.steps().iter().map(|step| match step {
+        ForcingStep::NakedSingle(candidate) => all_of(CellSet::from(candidate.cell())),
+        ForcingStep::HiddenSingle(candidate, group) => of_digit(CellSet::from(*group), candidate.digit()),
+    });
+
+    let contradiction = branch.contradiction().map(|contradiction| match contradiction {
+        ForcingContradiction::EmptyCell(cell) => all_of(CellSet::from(cell)),
+        ForcingContradiction::MissingDigit(digit, group) => of_digit(CellSet::from(group), digit),
+    });
+
+    singles.chain(contradiction).fold(CandidateSet::empty(), |scope, candidates| scope.union(&candidates))
+}
+
+//  Returns the candidates whose absence the guess relies on: those of its branch, and of the further guesses, which
+//  cover all the digits possible within their cell.
+fn guess_scope(guess: &Guess) -> CandidateSet {
+    guess.nested()
+        .iter()
+        .map(|nested| all_of(CellSet::from(nested.branch().assumption().cell())).union(&guess_scope(nested)))
+        .fold(branch_scope(guess.branch()), |scope, candidates| scope.union(&candidates))
+}
+
+fn of_digit(cells: CellSet, digit: Digit) -> CandidateSet {
+    cells.into_iter().map(|cell| Candidate::new(cell, digit)).collect()
+}
+
+fn all_of(cells: CellSet) -> CandidateSet {
+    cells.into_iter()
+        .flat_map(|cell| DigitSet::full().into_iter().map(move |digit| Candidate::new(cell, digit)))
+        .collect()
+}
+
+fn cells_of_groups(groups: GroupSet) -> CellSet {
+    groups.into_iter().fold(CellSet::empty(), |cells, group| cells.union(&CellSet::from(group)))
+}
+
@@ -1,18 +1,30 @@
 //! A refinement to the set of possible values of a cell.
 
-use crate::model::{CellIndex, CellSet, Digit, DigitSet, Group};
+use std::{hash::{Hash, Hasher}, sync::Arc};
+
+use crate::model::{CandidateSet, CellIndex, CellSet, Digit, DigitSet, Group, GroupSet};
+use super::{AlmostLockedSet, ChainNode, ForcingBranch, Guess, Premise};
 
 /// A refinement to the set of possible values of a cell.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+///
+/// The premises are not part of its identity: a refinement equals any other of the same removal for the same reason,
+/// premises or not.
+#[derive(Clone, Debug)]
 pub struct Refinement {
     cell: CellIndex,
     removed: Digit,
     reason: RefinementReason,
+    premises: Arc<[Premise]>,
 }
 
 impl Refinement {
-    /// Creates an instance.
-    pub fn new(cell: CellIndex, removed: Digit, reason: RefinementReason) -> Self { Self { cell, removed, reason, } }
+    /// Creates an instance, without premises.
+    pub fn new(cell: CellIndex, removed: Digit, reason: RefinementReason) -> Self {
+        Self { cell, removed, reason, premises: Arc::new([]), }
+    }
+
+    /// Returns the instance, with the specified premises.
+    pub fn with_premises(self, premises: Arc<[Premise]>) -> Self { Self { premises, ..self } }
 
     /// Returns the cell in which the digit was removed.
     pub fn cell(&self) -> CellIndex { self.cell }
@@ -21,11 +33,26 @@ impl Refinement {
     pub fn removed(&self) -> Digit { self.removed }
 
     /// Returns the reason the removal occurred.
-    pub fn reason(&self) -> RefinementReason { self.reason }
+    pub fn reason(&self) -> &RefinementReason { &self.reason }
+
+    /// Returns the earlier placements and refinements the removal depends upon.
+    pub fn premises(&self) -> &[Premise] { &self.premises }
+}
+
+impl PartialEq for Refinement {
+    fn eq(&self, other: &Self) -> bool {
+        (self.cell, self.removed, &self.reason) == (other.cell, other.removed, &other.reason)
+    }
+}
+
+impl Eq for Refinement {}
+
+impl Hash for Refinement {
+    fn hash<H: Hasher>(&self, state: &mut H) { (self.cell, self.removed, &self.reason).hash(state) }
 }
 
 /// The reason for which a refinement occurred.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum RefinementReason {
     /// CellExclusion: the digit which was placed in the cell.
     CellExclusion(Digit),
@@ -37,4 +64,143 @@ pub enum RefinementReason {
     GroupOverlap(Group, Group),
     /// GroupSubsetInclusion: subset of cells and digits, and the group guiding the removal.
     GroupSubsetInclusion(CellSet, DigitSet, Group),
+    /// Fish: the base groups, the cover groups, and the fins, if any.
+    Fish(GroupSet, GroupSet, CellSet),
+    /// XCycle: the kind of chain, and its nodes, in order, starting with a strong link.
+    XCycle(ChainKind, Arc<[ChainNode]>),
+    /// BivalueUniversalGrave: the digit which must be placed in the cell to avoid the deadly pattern.
+    BivalueUniversalGrave(Digit),
+    /// UniqueRectangle: the type of rectangle, the 4 corners of the rectangle, and the pair of digits it must avoid.
+    UniqueRectangle(UniqueRectangleKind, CellSet, DigitSet),
+    /// AvoidableRectangle: the type of rectangle, the 4 corners of the rectangle, and the pair of digits it must avoid.
+    AvoidableRectangle(UniqueRectangleKind, CellSet, DigitSet),
+    /// SueDeCoq: the cells of the overlap, the ALS within the rest of the line, and the ALS within the rest of the
+    /// square.
+    SueDeCoq(CellSet, AlmostLockedSet, AlmostLockedSet),
+    /// AlmostLockedSetXZ: the 2 ALSs, and their restricted common digits.
+    AlmostLockedSetXZ(AlmostLockedSet, AlmostLockedSet, DigitSet),
+    /// AlmostLockedSetXYWing: the 2 wing ALSs, and the pivot ALS.
+    AlmostLockedSetXYWing(AlmostLockedSet, AlmostLockedSet, AlmostLockedSet),
+    /// AlmostLockedSetChain: the ALSs of the chain, in order, and the restricted commons between consecutive ALSs.
+    AlmostLockedSetChain(Arc<[AlmostLockedSet]>, Arc<[Digit]>),
+    /// DeathBlossom: the stem cell, and the petal ALS attached to each of its possible digits.
+    DeathBlossom(CellIndex, Arc<[(Digit, AlmostLockedSet)]>),
+    /// AlternatingInferenceChain: the kind of chain, and its nodes, in order, starting with a strong link.
+    AlternatingInferenceChain(ChainKind, Arc<[ChainNode]>),
+    /// Exocet: the base cells, the target cells, and the cross-lines.
+    Exocet(CellSet, CellSet, GroupSet),
+    /// Medusa: the rule which applied, and the 2 colors of the cluster, the false color first when the rule proves a
+    /// color false.
+    Medusa(MedusaRule, CandidateSet, CandidateSet),
+    /// Nishio: the placements of the digit following from its assumption, starting with the assumption itself, and the
+    /// groups which cannot all hold the digit, either a single group without any position, or rows whose positions lie
+    /// within fewer columns.
+    Nishio(Arc<[CellIndex]>, GroupSet),
+    /// PatternOverlay: the digit, and the number of templates remaining for it.
+    PatternOverlay(Digit, usize),
+    /// ForcingChain: the kind of forcing chain, and its branches, one per assumption.
+    ForcingChain(ForcingChainKind, Arc<[ForcingBranch]>),
+    /// BowmansBingo: the guess which failed.
+    ///
+    /// This is trial and error, not logic: it only occurs once every other analysis is stalled.
+    BowmansBingo(Arc<Guess>),
+    /// User: the candidate was eliminated by the user, as a pencil mark.
+    User,
+}
+
+impl RefinementReason {
+    /// Returns whether the refinement results from a guess, rather than from logic.
+    pub fn is_guess(&self) -> bool { matches!(self, RefinementReason::BowmansBingo(_)) }
+
+    /// Returns the name of the technique, for display.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RefinementReason::CellExclusion(_) => "CellExclusion",
+            RefinementReason::GroupExclusion(..) => "GroupExclusion",
+            RefinementReason::GroupInclusion(..) => "GroupInclusion",
+            RefinementReason::GroupOverlap(..) => "GroupOverlap",
+            RefinementReason::GroupSubsetInclusion(..) => "GroupSubsetInclusion",
+            RefinementReason::Fish(..) => "Fish",
+            RefinementReason::XCycle(..) => "XCycle",
+            RefinementReason::BivalueUniversalGrave(_) => "BivalueUniversalGrave",
+            RefinementReason::UniqueRectangle(..) => "UniqueRectangle",
+            RefinementReason::AvoidableRectangle(..) => "AvoidableRectangle",
+            RefinementReason::SueDeCoq(..) => "SueDeCoq",
+            RefinementReason::AlmostLockedSetXZ(..) => "AlmostLockedSetXZ",
+            RefinementReason::AlmostLockedSetXYWing(..) => "AlmostLockedSetXYWing",
+            RefinementReason::AlmostLockedSetChain(..) => "AlmostLockedSetChain",
+            RefinementReason::DeathBlossom(..) => "DeathBlossom",
+            RefinementReason::AlternatingInferenceChain(..) => "AlternatingInferenceChain",
+            RefinementReason::Exocet(..) => "Exocet",
+            RefinementReason::Medusa(..) => "Medusa",
+            RefinementReason::Nishio(..) => "Nishio",
+            RefinementReason::PatternOverlay(..) => "PatternOverlay",
+            RefinementReason::ForcingChain(..) => "ForcingChain",
+            RefinementReason::BowmansBingo(_) => "BowmansBingo",
+            RefinementReason::User => "User",
+        }
+    }
+}
+
+/// The type of a unique, or avoidable, rectangle.
+///
+/// In the following, the floor is formed by the corners containing only the pair of digits, and the roof by the other
+/// corners.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UniqueRectangleKind {
+    /// A single corner forms the roof, hence cannot hold either digit of the pair.
+    Type1,
+    /// The roof is formed by 2 corners sharing a group, with a single extra digit which cannot appear in their peers.
+    Type2,
+    /// The roof is formed by 2 corners sharing a group, whose extra digits form a subset with the cells and digits.
+    Type3(CellSet, DigitSet),
+    /// The roof is formed by 2 corners sharing a group, in which one digit of the pair is only possible in the roof,
+    /// hence the other digit of the pair cannot appear in the roof.
+    Type4,
+    /// The roof is formed by 2 diagonal or 3 corners, with a single extra digit which cannot appear in their peers.
+    Type5,
+    /// The roof is formed by 2 diagonal corners, and one digit of the pair is only possible in the rectangle for its
+    /// 2 rows and 2 columns, hence cannot appear in the roof.
+    Type6,
+    /// One digit of the pair is only possible in the rectangle for both the row and column of the corner opposite a
+    /// corner of the floor, hence the other digit of the pair cannot appear in this opposite corner.
+    Hidden,
+}
+
+/// The rule of 3D Medusa which led to a refinement.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MedusaRule {
+    /// Two candidates of the same color within a cell, hence this color is false.
+    TwiceInCell,
+    /// Two candidates of the same color and digit within a group, hence this color is false.
+    TwiceInGroup,
+    /// A cell with candidates of both colors, hence its uncolored candidates are false.
+    TwoColorsInCell,
+    /// An uncolored candidate seeing the same digit in both colors, hence is false.
+    TwoColorsElsewhere,
+    /// An uncolored candidate in a cell with a candidate of one color, seeing the same digit in the other color, hence
+    /// is false.
+    TwoColorsGroupAndCell,
+    /// An uncolored cell, all the candidates of which see the same digit in the same color, hence this color is false.
+    CellEmptiedByColor,
+}
+
+/// The kind of a forcing chain.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ForcingChainKind {
+    /// The assumption of the single branch led to a contradiction, hence is false.
+    Contradiction,
+    /// Each digit possible within the cell was assumed in turn, and all branches led to the refinement.
+    Cell(CellIndex),
+    /// Each position of the digit within the group was assumed in turn, and all branches led to the refinement.
+    Group(Digit, Group),
+}
+
+/// The kind of a chain, alternating strong and weak links.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChainKind {
+    /// The chain starts and ends with a strong link, hence either its first or last node is true.
+    Open,
+    /// The last node of the chain is weakly linked to the first, hence every weak link is also strong.
+    ContinuousLoop,
 }
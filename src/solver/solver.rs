@@ -1,16 +1,35 @@
 //! The Solver itself.
 
-use std::{error, fmt};
+use std::{collections::HashSet, error, fmt};
 
-use crate::model::{DIMENSION, CellIndex, Digit, Grid};
-use super::{Analyzer, JournalReader, JournalWriter, Placement, Placer, PossibleValues, Refinement};
+use crate::model::{DIMENSION, Candidate, CellIndex, Digit, Grid};
+use super::{
+    find_solutions, Analyzer, AnalyzerConfig, AnalyzerSnapshot, Hint, JournalReader, JournalWriter, Mistake,
+    MistakeMode, Placement, Placer, PlacerSnapshot, PossibleValues, Premise, Refinement, RefinementReason, Step,
+};
+use super::provenance;
 
 /// The didactic solver assistant.
-#[derive(Clone, Debug)]
+///
+/// A clone has its own copy of the journals, so that moves on either never affect the other.
+#[derive(Debug)]
 pub struct Solver {
+    givens: Grid,
     grid: Grid,
+    //  The solution, if known: unique, or given.
+    solution: Option<Grid>,
+    //  How mistakes of the user are handled, once the solution is known.
+    mistake_mode: MistakeMode,
     analyzer: Analyzer,
     placer: Placer,
+    //  Snapshots prior to each move, most recent last.
+    undo: Vec<Snapshot>,
+    //  Snapshots following each undone move, most recently undone last, with the placements and refinements undone.
+    redo: Vec<(Snapshot, Vec<Placement>, Vec<Refinement>)>,
+    //  The number of placements explained by hints so far, and the entries they transitively depend upon, so that each
+    //  hint only explains its own; forgotten whenever the journals are rolled back.
+    explained_placements: usize,
+    explained: HashSet<Premise>,
 }
 
 impl Solver {
@@ -27,9 +46,23 @@ impl Solver {
             }
         }
 
-        Solver { grid, analyzer, placer, }
+        Solver {
+            givens: grid,
+            grid,
+            solution: None,
+            mistake_mode: MistakeMode::default(),
+            analyzer,
+            placer,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            explained_placements: grid.number_solved(),
+            explained: HashSet::new(),
+        }
     }
 
+    /// Returns the original grid, with only the givens.
+    pub fn givens(&self) -> Grid { self.givens }
+
     /// Returns the current grid.
     pub fn grid(&self) -> Grid { self.grid }
 
@@ -44,33 +77,248 @@ impl Solver {
 
     /// Sets the specified digit in the specified cell, if allowed.
     ///
-    /// Returns an error if the digit is not allowed as per the rules of Sudoku.
-    pub fn set_digit(&mut self, cell: CellIndex, digit: Digit) -> Result<(), ConflictError> {
+    /// Should the solution be known, placing another digit than the solution's is a mistake, handled as per the
+    /// mistake mode.
+    ///
+    /// Returns an error if the digit is not allowed as per the rules of Sudoku, or is a mistake in strict mode.
+    /// Otherwise returns the mistake, in lenient mode, if proven by logic.
+    pub fn set_digit(&mut self, cell: CellIndex, digit: Digit) -> Result<Option<Mistake>, MoveError> {
         //  Check for conflicts before any modification.
         if let Some(conflicting) = self.grid.get_conflicting(cell, digit) {
-            return Err(ConflictError{ digit, candidate: cell, conflicting });
+            return Err(MoveError::Conflict(ConflictError{ digit, candidate: cell, conflicting }));
         }
 
+        let mistake = self.check_move(cell, digit, true)?;
+
+        self.record(self.snapshot());
+
         //  Mark as resolved.
         self.grid.set_digit(cell, Some(digit));
         self.placer.set_digit(cell, digit);
 
+        Ok(mistake)
+    }
+
+    /// Eliminates the specified digit from the candidates of the specified cell, as a pencil mark.
+    ///
+    /// The elimination is recorded as a refinement, from which the analyses then reason. Eliminating a digit which is
+    /// no longer possible does nothing. See `restore` to restore the candidate later on.
+    ///
+    /// Should the solution be known, eliminating the digit of the solution is a mistake, handled as per the mistake
+    /// mode.
+    ///
+    /// Returns an error if the digit is the last candidate of the cell, or is a mistake in strict mode. Otherwise
+    /// returns the mistake, in lenient mode, if proven by logic.
+    pub fn eliminate(&mut self, cell: CellIndex, digit: Digit) -> Result<Option<Mistake>, MoveError> {
+        let candidates = self.possible_values().of_cell(cell);
+
+        if !candidates.has(digit) {
+            return Ok(None);
+        }
+
+        if candidates.size() == 1 {
+            return Err(MoveError::Elimination(EliminationError{ digit, cell }));
+        }
+
+        let mistake = self.check_move(cell, digit, false)?;
+
+        self.record(self.snapshot());
+        self.analyzer.eliminate(cell, digit);
+
+        Ok(mistake)
+    }
+
+    /// Restores the specified digit among the candidates of the specified cell, undoing its elimination by the user.
+    ///
+    /// The moves following the elimination are undone, then replayed: the digits set and candidates eliminated by the
+    /// user as they were, the placements and refinements of the solver by running them anew, hence those depending
+    /// upon the elimination are rolled back, unless logic alone reaches them anew. The elimination itself is gone from
+    /// the moves to undo, and the moves undone so far are discarded.
+    ///
+    /// Returns whether the candidate was restored, that is whether the user had eliminated it.
+    pub fn restore(&mut self, cell: CellIndex, digit: Digit) -> bool {
+        let refinements = self.refinements().get_events();
+
+        let Some(index) = refinements.iter().rposition(|refinement| {
+            refinement.cell() == cell && refinement.removed() == digit && *refinement.reason() == RefinementReason::User
+        }) else {
+            return false;
+        };
+
+        //  Every move appends to the journals, hence undoing down to the elimination undoes the elimination last.
+        self.redo.clear();
+
+        while self.refinements().len() > index && self.undo() {}
+
+        let mut moves = std::mem::take(&mut self.redo);
+
+        //  The elimination itself.
+        moves.pop();
+
+        for (_, placements, refinements) in moves.into_iter().rev() {
+            match (&placements[..], &refinements[..]) {
+                ([placement], []) if placement.premises().is_empty() => {
+                    let (cell, digit) = (placement.cell(), placement.digit());
+
+                    if self.grid.get_digit(cell).is_none() && self.grid.get_conflicting(cell, digit).is_none() {
+                        self.record(self.snapshot());
+                        self.grid.set_digit(cell, Some(digit));
+                        self.placer.set_digit(cell, digit);
+                    }
+                },
+                ([], [refinement]) if *refinement.reason() == RefinementReason::User => {
+                    let (cell, digit) = (refinement.cell(), refinement.removed());
+
+                    let candidates = self.possible_values().of_cell(cell);
+
+                    if candidates.has(digit) && candidates.size() > 1 {
+                        self.record(self.snapshot());
+                        self.analyzer.eliminate(cell, digit);
+                    }
+                },
+                ([_], []) => { let _ = self.place(); },
+                _ => { let _ = self.refine(); },
+            }
+        }
+
+        true
+    }
+
+    /// Assumes the grid has a unique solution, enabling the analyses relying on this assumption.
+    ///
+    /// Returns an error, leaving those analyses disabled, if the givens do not lead to exactly one solution.
+    pub fn assume_uniqueness(&mut self) -> Result<(), UniquenessError> {
+        let solutions = find_solutions(&self.givens, 2);
+
+        if solutions.len() != 1 {
+            return Err(UniquenessError{ multiple: solutions.len() > 1 });
+        }
+
+        self.solution = Some(solutions[0]);
+        self.analyzer.assume_uniqueness(self.givens);
+
         Ok(())
     }
 
+    /// Sets the solution, against which the moves of the user are checked for mistakes.
+    ///
+    /// Unlike `assume_uniqueness`, the solution is trusted as is, and the analyses relying on uniqueness are left
+    /// disabled.
+    pub fn set_solution(&mut self, solution: Grid) { self.solution = Some(solution); }
+
+    /// Returns the solution, if known.
+    pub fn solution(&self) -> Option<Grid> { self.solution }
+
+    /// Returns how mistakes of the user are handled.
+    pub fn mistake_mode(&self) -> MistakeMode { self.mistake_mode }
+
+    /// Sets how mistakes of the user are handled, once the solution is known.
+    pub fn set_mistake_mode(&mut self, mode: MistakeMode) { self.mistake_mode = mode; }
+
+    /// Returns the configuration of the analyses.
+    pub fn config(&self) -> &AnalyzerConfig { self.analyzer.config() }
+
+    /// Configures the analyses: which to run, in which order, and within which limits.
+    ///
+    /// For example, to restrict the solver to the techniques learned so far.
+    pub fn configure(&mut self, config: AnalyzerConfig) { self.analyzer.configure(config); }
+
+    /// Returns the index, within the refinements, of the first guess, if any: this is where logic ran out.
+    pub fn first_guess(&self) -> Option<usize> {
+        self.refinements().get_events().iter().position(|refinement| refinement.reason().is_guess())
+    }
+
+    /// Explains why the digit is not possible within the cell.
+    ///
+    /// Returns the placements and refinements which led to its removal, trimmed to those it transitively depends upon,
+    /// each after all of its premises and the removal itself last; or None if the digit is still possible.
+    pub fn why_not(&self, cell: CellIndex, digit: Digit) -> Option<Vec<Step>> {
+        let refinements = self.refinements().get_events();
+        let target = refinements.iter()
+            .position(|refinement| refinement.cell() == cell && refinement.removed() == digit)?;
+
+        Some(provenance::explain(&self.placements().get_events(), &refinements, &[Premise::Refinement(target)]))
+    }
+
+    /// Returns the minimal path to the current grid, as a walkthrough to follow once solved.
+    ///
+    /// Only the placements beyond the givens, and the placements and refinements they transitively depend upon, are
+    /// kept: the refinements which never contributed to any placement are dropped. Each step appears after all of its
+    /// premises, and the placements in the order they were made.
+    pub fn minimal_path(&self) -> Vec<Step> {
+        let placements = self.placements().get_events();
+
+        //  The givens are placed first, on creation.
+        let givens = self.givens.number_solved();
+        let targets: Vec<Premise> = (givens..placements.len()).map(Premise::Placement).collect();
+
+        provenance::explain(&placements, &self.refinements().get_events(), &targets)
+            .into_iter()
+            .filter(|step| !matches!(step, Step::Placement(index, _) if *index < givens))
+            .collect()
+    }
+
+    /// Verifies the placements and refinements made so far against the solution.
+    ///
+    /// Returns an error with the first unsound step: a placement of another digit than the solution's, or the removal
+    /// of the solution's digit. Placements made without premises, such as the givens and the user's, are checked
+    /// first, then the refinements in order, then the placements deduced from them.
+    pub fn verify(&self, solution: &Grid) -> Result<(), UnsoundDeduction> {
+        let placements = self.placements().get_events();
+
+        let is_unsound = |placement: &Placement| {
+            solution.get_digit(placement.cell()).is_some_and(|digit| digit != placement.digit())
+        };
+
+        let unsound_placement = |premises: bool| {
+            placements.iter()
+                .enumerate()
+                .filter(|(_, placement)| placement.premises().is_empty() != premises)
+                .find(|(_, placement)| is_unsound(placement))
+                .map(|(index, placement)| Step::Placement(index, placement.clone()))
+        };
+
+        let unsound_refinement = || {
+            self.refinements()
+                .get_events()
+                .into_iter()
+                .enumerate()
+                .find(|(_, refinement)| solution.get_digit(refinement.cell()) == Some(refinement.removed()))
+                .map(|(index, refinement)| Step::Refinement(index, Box::new(refinement)))
+        };
+
+        let step = unsound_placement(false).or_else(unsound_refinement).or_else(|| unsound_placement(true));
+
+        match step {
+            Some(step) => Err(UnsoundDeduction{ step }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the placements and refinements which directly depend upon the specified one: what it unlocked.
+    ///
+    /// The placements come first, then the refinements, each in the order of their journal.
+    pub fn dependents(&self, premise: Premise) -> Vec<Premise> {
+        provenance::dependents(&self.placements().get_events(), &self.refinements().get_events(), premise)
+    }
+
     /// Solves the grid completely, if possible.
     ///
-    /// Returns an error if no progress can be made.
+    /// Once logic runs out, guesses are made, hence a grid with a unique solution is solved, unless guessing is disabled,
+    /// or limited to too shallow guesses, by the configuration; see `first_guess` to tell whether logic sufficed.
+    ///
+    /// Returns an error if no progress can be made, which only occurs if the grid has no, or multiple, solutions, or if
+    /// the guesses needed are deeper than configured.
     pub fn solve(&mut self) -> Result<(), ProgressStalled> {
         while self.grid.number_solved() < DIMENSION * DIMENSION {
             #[cfg(debug_assertions)]
             eprintln!("Solver::solve - {} cells solved so far", self.grid.number_solved());
 
-            if let Ok(_) = self.place() {
+            if self.place().is_ok() {
                 continue;
             }
 
-            if let Ok(_) = self.refine() {
+            if self.refine().is_ok() {
                 continue;
             }
 
@@ -85,7 +333,11 @@ impl Solver {
     /// Returns whether any placement occurred, or not.
     pub fn place(&mut self) -> Result<(), ProgressStalled> {
         while !self.placer.is_done() {
+            let snapshot = self.snapshot();
+
             if let Some(placement) = self.placer.handle_next_refinement() {
+                self.record(snapshot);
+
                 self.grid.set_digit(placement.cell(), Some(placement.digit()));
 
                 return Ok(());
@@ -95,20 +347,196 @@ impl Solver {
         Err(ProgressStalled{})
     }
 
+    /// Advances to the next placement, refining as needed: the unit of progress of a hint.
+    ///
+    /// Returns the placement, along with the refinements which justified it and no earlier placement, grouped by
+    /// technique; hence the hints following one another from the givens form the minimal path.
+    ///
+    /// Returns an error if no placement can be made.
+    pub fn advance_to_next_placement(&mut self) -> Result<Hint, ProgressStalled> {
+        while self.place().is_err() {
+            self.refine()?;
+        }
+
+        let (placements, refinements) = (self.placements().get_events(), self.refinements().get_events());
+
+        let index = placements.len() - 1;
+
+        //  The entries the earlier placements depend upon are not part of the justification, and mostly known already.
+        let earlier: Vec<Premise> = (self.explained_placements..index).map(Premise::Placement).collect();
+        provenance::explain_anew(&placements, &refinements, &earlier, &mut self.explained);
+
+        let justification = provenance::explain_anew(
+            &placements,
+            &refinements,
+            &[Premise::Placement(index)],
+            &mut self.explained,
+        );
+
+        let justification = justification.into_iter()
+            .filter_map(|step| match step {
+                Step::Placement(..) => None,
+                Step::Refinement(_, refinement) => Some(*refinement),
+            })
+            .collect();
+
+        self.explained_placements = index + 1;
+
+        Ok(Hint::new(placements[index].clone(), justification))
+    }
+
     /// Refines the set of possible values, if possible.
     ///
-    /// Returns whether any refinement occurred, or not.
+    /// Returns whether any refinement occurred, or not: none does once a cell is left without candidates.
     pub fn refine(&mut self) -> Result<(), ProgressStalled> {
+        if self.is_contradictory() {
+            return Err(ProgressStalled{});
+        }
+
         while !self.analyzer.is_done() {
+            let snapshot = self.snapshot();
             let refined = self.analyzer.analyze();
 
             if refined > 0 {
+                self.record(snapshot);
                 return Ok(());
             }
         }
 
         Err(ProgressStalled{})
     }
+
+    /// Returns whether any move can be undone.
+    pub fn can_undo(&self) -> bool { !self.undo.is_empty() }
+
+    /// Returns whether any undone move can be redone.
+    pub fn can_redo(&self) -> bool { !self.redo.is_empty() }
+
+    /// Undoes the latest move: digit set, placement, or refinement.
+    ///
+    /// The grid, possible values, and journals are rolled back to their state prior to the move. Making a new move
+    /// afterwards discards the moves undone so far.
+    ///
+    /// Returns whether a move was undone.
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.undo.pop() else { return false };
+
+        let current = self.snapshot();
+        let (placements, refinements) = self.restore_snapshot(&previous);
+
+        self.redo.push((current, placements, refinements));
+
+        true
+    }
+
+    /// Redoes the latest move undone.
+    ///
+    /// Returns whether a move was redone.
+    pub fn redo(&mut self) -> bool {
+        let Some((next, placements, refinements)) = self.redo.pop() else { return false };
+
+        self.undo.push(self.snapshot());
+
+        self.placer.reappend(placements);
+        self.analyzer.reappend(refinements);
+        self.restore_snapshot(&next);
+
+        true
+    }
+}
+
+//
+//  Implementation Details
+//
+
+//  A snapshot of the state of the Solver, prior to or following a move.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    grid: Grid,
+    analyzer: AnalyzerSnapshot,
+    placer: PlacerSnapshot,
+}
+
+impl Solver {
+    fn snapshot(&self) -> Snapshot {
+        Snapshot { grid: self.grid, analyzer: self.analyzer.snapshot(), placer: self.placer.snapshot(), }
+    }
+
+    //  Restores the snapshot, returning the placements and refinements truncated.
+    fn restore_snapshot(&mut self, snapshot: &Snapshot) -> (Vec<Placement>, Vec<Refinement>) {
+        self.grid = snapshot.grid;
+        self.explained_placements = self.givens.number_solved();
+        self.explained.clear();
+
+        let placements = self.placer.restore(&snapshot.placer);
+        let refinements = self.analyzer.restore(&snapshot.analyzer);
+
+        (placements, refinements)
+    }
+
+    //  Returns whether a cell is left without candidates, as the moves of the user contradict one another: the analyses
+    //  cannot proceed from there.
+    fn is_contradictory(&self) -> bool {
+        let possible_values = self.possible_values();
+
+        CellIndex::all().any(|cell| possible_values.of_cell(cell).is_empty())
+    }
+
+    //  Checks a move of the user against the solution, if known.
+    //
+    //  Returns an error in strict mode, or the mistake in lenient mode if proven by logic.
+    fn check_move(&self, cell: CellIndex, digit: Digit, placement: bool) -> Result<Option<Mistake>, MoveError> {
+        let Some(solution) = self.solution.and_then(|solution| solution.get_digit(cell)) else { return Ok(None) };
+
+        if (solution == digit) == placement {
+            return Ok(None);
+        }
+
+        if self.mistake_mode == MistakeMode::Strict {
+            return Err(MoveError::Mistake(Mistake::new(cell, digit, placement, Vec::new())));
+        }
+
+        //  The placement is refuted by itself, the elimination by refuting every other candidate.
+        let refuted: Vec<Digit> = if placement {
+            vec![digit]
+        } else {
+            self.possible_values().of_cell(cell).into_iter().filter(|other| *other != digit).collect()
+        };
+
+        let proof: Option<Vec<_>> = refuted.into_iter()
+            .map(|digit| self.analyzer.refute(Candidate::new(cell, digit)))
+            .collect();
+
+        Ok(proof.map(|proof| Mistake::new(cell, digit, placement, proof)))
+    }
+
+    //  Records a move, made from the state of the snapshot, discarding the moves undone so far.
+    fn record(&mut self, snapshot: Snapshot) {
+        self.undo.push(snapshot);
+        self.redo.clear();
+    }
+}
+
+impl Clone for Solver {
+    fn clone(&self) -> Self {
+        let placements = JournalWriter::from(self.placements().get_events());
+
+        let analyzer = self.analyzer.fork(placements.reader());
+        let placer = self.placer.fork(placements, analyzer.refinements());
+
+        Solver {
+            givens: self.givens,
+            grid: self.grid,
+            solution: self.solution,
+            mistake_mode: self.mistake_mode,
+            analyzer,
+            placer,
+            undo: self.undo.clone(),
+            redo: self.redo.clone(),
+            explained_placements: self.explained_placements,
+            explained: self.explained.clone(),
+        }
+    }
 }
 
 /// A conflict error within a group.
@@ -128,6 +556,45 @@ impl fmt::Display for ConflictError {
 
 impl error::Error for ConflictError {}
 
+/// An elimination error, the candidate being the last of its cell.
+#[derive(Clone, Debug)]
+pub struct EliminationError {
+    digit: Digit,
+    cell: CellIndex,
+}
+
+impl fmt::Display for EliminationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Cannot eliminate {:?} from ({:?}, {:?}), for it is the last candidate",
+            self.digit, self.cell.row(), self.cell.column())
+    }
+}
+
+impl error::Error for EliminationError {}
+
+/// An error of a move of the user: placement, or elimination.
+#[derive(Clone, Debug)]
+pub enum MoveError {
+    /// The digit conflicts with another within a group.
+    Conflict(ConflictError),
+    /// The digit is the last candidate of the cell.
+    Elimination(EliminationError),
+    /// The move contradicts the solution, in strict mode.
+    Mistake(Mistake),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            MoveError::Conflict(e) => e.fmt(f),
+            MoveError::Elimination(e) => e.fmt(f),
+            MoveError::Mistake(e) => e.fmt(f),
+        }
+    }
+}
+
+impl error::Error for MoveError {}
+
 /// Progress is stalled.
 #[derive(Clone, Debug)]
 pub struct ProgressStalled {}
@@ -139,3 +606,511 @@ impl fmt::Display for ProgressStalled {
 }
 
 impl error::Error for ProgressStalled {}
+
+/// A placement or refinement contradicting the solution.
+#[derive(Clone, Debug)]
+pub struct UnsoundDeduction {
+    step: Step,
+}
+
+impl UnsoundDeduction {
+    /// Returns the unsound step, with its reason.
+    pub fn step(&self) -> &Step { &self.step }
+}
+
+impl fmt::Display for UnsoundDeduction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match &self.step {
+            Step::Placement(index, placement) => {
+                write!(f, "Unsound placement #{index} of {:?} at ({:?}, {:?})",
+                    placement.digit(), placement.cell().row(), placement.cell().column())
+            },
+            Step::Refinement(index, refinement) => {
+                let cell = refinement.cell();
+
+                write!(f, "Unsound refinement #{index} removing {:?} from ({:?}, {:?}), due to {}",
+                    refinement.removed(), cell.row(), cell.column(), refinement.reason().name())
+            },
+        }
+    }
+}
+
+impl error::Error for UnsoundDeduction {}
+
+/// The grid does not have a unique solution.
+#[derive(Clone, Debug)]
+pub struct UniquenessError {
+    multiple: bool,
+}
+
+impl fmt::Display for UniquenessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.multiple {
+            write!(f, "The grid has multiple solutions")
+        } else {
+            write!(f, "The grid has no solution")
+        }
+    }
+}
+
+impl error::Error for UniquenessError {}
+
+#[cfg(test)]
+mod tests {
+
+use super::*;
+use crate::model::CellSet;
+use crate::solver::{DEFAULT_MAX_GUESS_DEPTH, Analysis, JournalCursor};
+use crate::test_utils::{cell, column, digit, parse, row, square};
+
+const GRID: &str = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+
+#[test]
+fn undo_redo_round_trip() {
+    let mut solver = Solver::new(parse(GRID));
+    let initial = solver.possible_values();
+
+    solver.solve().expect("Solvable");
+
+    let (grid, possible_values) = (solver.grid(), solver.possible_values());
+    let (placements, refinements) = (solver.placements().get_events(), solver.refinements().get_events());
+
+    while solver.undo() {}
+
+    assert_eq!(parse(GRID), solver.grid());
+    assert_eq!(initial, solver.possible_values());
+    assert!(solver.refinements().is_empty());
+    assert!(!solver.can_undo());
+
+    while solver.redo() {}
+
+    assert_eq!(grid, solver.grid());
+    assert_eq!(possible_values, solver.possible_values());
+    assert_eq!(placements, solver.placements().get_events());
+    assert_eq!(refinements, solver.refinements().get_events());
+    assert!(!solver.can_redo());
+}
+
+#[test]
+fn undo_with_outside_cursor() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let mut cursor = JournalCursor::new(solver.refinements());
+    while cursor.handle_next(|_| ()).is_some() {}
+
+    while solver.undo() {}
+
+    assert!(cursor.is_done());
+    assert!(cursor.handle_next(|_| ()).is_none());
+}
+
+#[test]
+fn undo_on_clone() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let (placements, refinements) = (solver.placements().get_events(), solver.refinements().get_events());
+
+    let mut clone = solver.clone();
+
+    while clone.undo() {}
+
+    assert!(clone.refinements().is_empty());
+    assert_eq!(placements, solver.placements().get_events());
+    assert_eq!(refinements, solver.refinements().get_events());
+
+    while clone.redo() {}
+
+    assert_eq!(solver.grid(), clone.grid());
+    assert_eq!(placements, clone.placements().get_events());
+    assert_eq!(refinements, clone.refinements().get_events());
+}
+
+#[test]
+fn undo_then_move() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let grid = solver.grid();
+
+    for _ in 0..10 {
+        assert!(solver.undo());
+    }
+
+    assert_ne!(grid, solver.grid());
+
+    solver.solve().expect("Solvable");
+
+    assert_eq!(grid, solver.grid());
+    assert!(!solver.can_redo());
+}
+
+#[test]
+fn eliminate_checked_against_solution() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.assume_uniqueness().expect("Unique");
+
+    //  The solution of the first cell is 4.
+    let cell = CellIndex::new(0).expect("Valid CellIndex");
+    let (right, wrong) = (Digit::new(4).expect("Valid Digit"), Digit::new(1).expect("Valid Digit"));
+
+    assert!(solver.eliminate(cell, right).is_err());
+    assert!(solver.eliminate(cell, wrong).is_ok());
+
+    let refinements = solver.refinements().get_events();
+
+    assert_eq!(Some(&Refinement::new(cell, wrong, RefinementReason::User)), refinements.last());
+    assert!(!solver.possible_values().of_cell(cell).has(wrong));
+
+    assert!(solver.undo());
+    assert!(solver.possible_values().of_cell(cell).has(wrong));
+}
+
+#[test]
+fn restore() {
+    let mut solver = Solver::new(parse(GRID));
+    let solution = find_solutions(&parse(GRID), 1)[0];
+
+    solver.advance_to_next_placement().expect("Placement");
+
+    //  Eliminating the solution of a cell with 2 candidates leads to placing the other.
+    let cell = CellIndex::all().find(|cell| solver.possible_values().of_cell(*cell).size() == 2).expect("Bivalue cell");
+    let right = solution.get_digit(cell).expect("Solved");
+    let wrong = solver.possible_values().of_cell(cell).into_iter().find(|digit| *digit != right).expect("Other");
+
+    assert!(!solver.restore(cell, right));
+
+    solver.eliminate(cell, right).expect("Not the last candidate");
+
+    while solver.grid().get_digit(cell).is_none() {
+        solver.place().expect("Placement");
+    }
+
+    assert_eq!(Some(wrong), solver.grid().get_digit(cell));
+
+    //  A digit set afterwards by the user, independently.
+    let other = CellIndex::all()
+        .find(|other| solver.grid().get_digit(*other).is_none() && !CellSet::peers(cell).has(*other))
+        .expect("Unsolved cell");
+
+    solver.set_digit(other, solution.get_digit(other).expect("Solved")).expect("Allowed");
+
+    assert!(solver.restore(cell, right));
+
+    assert_eq!(None, solver.grid().get_digit(cell));
+    assert_eq!(solution.get_digit(other), solver.grid().get_digit(other));
+    assert!(solver.possible_values().of_cell(cell).has(right));
+    assert!(solver.possible_values().of_cell(cell).has(wrong));
+    assert!(solver.placements().get_events().iter().all(|placement| placement.cell() != cell));
+
+    assert!(!solver.restore(cell, right));
+}
+
+#[test]
+fn why_not() {
+    let mut solver = Solver::new(parse(GRID));
+
+    assert_eq!(None, solver.why_not(cell(5, 6), digit(1)));
+
+    let hint = solver.advance_to_next_placement().expect("Progress");
+
+    assert_eq!((cell(5, 6), digit(4)), (hint.placement().cell(), hint.placement().digit()));
+
+    //  A naked single: each other digit is removed by the exclusion of a given peer, and by nothing else.
+    let exclusions = [
+        (1, cell(4, 4), square(5)),
+        (2, cell(4, 6), column(6)),
+        (3, cell(8, 6), column(6)),
+        (5, cell(2, 6), column(6)),
+        (6, cell(3, 6), column(6)),
+        (7, cell(5, 1), row(5)),
+        (8, cell(5, 9), row(5)),
+        (9, cell(7, 6), column(6)),
+    ];
+
+    let mut premises = Vec::new();
+
+    for (removed, peer, group) in exclusions {
+        let steps = solver.why_not(cell(5, 6), digit(removed)).expect("Removed");
+
+        let [Step::Placement(given, placement), Step::Refinement(index, refinement)] = steps.as_slice() else {
+            panic!("Expected a given and its exclusion, got {steps:?}")
+        };
+
+        assert_eq!((peer, digit(removed)), (placement.cell(), placement.digit()));
+        assert!(placement.premises().is_empty());
+        assert_eq!(RefinementReason::GroupExclusion(peer, group), *refinement.reason());
+        assert_eq!([Premise::Placement(*given)], *refinement.premises());
+
+        premises.push(Premise::Refinement(*index));
+    }
+
+    premises.sort();
+
+    assert_eq!(premises, *hint.placement().premises());
+}
+
+#[test]
+fn why_not_forcing_chain() {
+    let mut solver = Solver::new(parse("005000080060020001007601000690300000000007800010000050000708000000000069300005100"));
+
+    solver.configure(AnalyzerConfig::only(&[Analysis::GroupExclusion, Analysis::GroupInclusion, Analysis::ForcingChain]));
+    solver.solve().expect("Solvable");
+
+    let refinements = solver.refinements().get_events();
+    let index = refinements.iter()
+        .position(|refinement| matches!(refinement.reason(), RefinementReason::ForcingChain(..)))
+        .expect("Forcing chain");
+
+    //  Assuming 9 in r1c4 leaves no 9 in column 6: only the removals of 9 from the rest of the column are premises.
+    let target = &refinements[index];
+
+    assert_eq!((cell(1, 4), digit(9)), (target.cell(), target.removed()));
+    assert!(!target.premises().is_empty());
+
+    for premise in target.premises() {
+        let Premise::Refinement(premise) = premise else { panic!("Expected a refinement, got {premise:?}") };
+
+        assert!(CellSet::from(column(6)).has(refinements[*premise].cell()));
+        assert_eq!(digit(9), refinements[*premise].removed());
+    }
+
+    let steps = solver.why_not(cell(1, 4), digit(9)).expect("Removed");
+
+    assert!(steps.len() < index / 10);
+}
+
+#[test]
+fn minimal_path() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let path = solver.minimal_path();
+    let placements = path.iter().filter(|step| matches!(step, Step::Placement(..))).count();
+
+    assert_eq!(solver.placements().len() - parse(GRID).number_solved(), placements);
+    assert!(path.len() < solver.refinements().len() + placements);
+
+    for (position, step) in path.iter().enumerate() {
+        let premises = match step {
+            Step::Placement(_, placement) => placement.premises(),
+            Step::Refinement(_, refinement) => refinement.premises(),
+        };
+
+        for premise in premises {
+            let found = path[..position].iter().any(|earlier| earlier.premise() == *premise);
+
+            assert!(found || matches!(premise, Premise::Placement(index) if *index < parse(GRID).number_solved()));
+        }
+    }
+}
+
+#[test]
+fn advance_to_next_placement() {
+    let mut solver = Solver::new(parse(GRID));
+
+    let mut refinements = 0;
+
+    while let Ok(hint) = solver.advance_to_next_placement() {
+        assert_eq!(solver.placements().get_events().last(), Some(hint.placement()));
+
+        refinements += hint.refinements().count();
+    }
+
+    assert_eq!(DIMENSION * DIMENSION, solver.grid().number_solved());
+
+    let path = solver.minimal_path();
+
+    assert_eq!(path.iter().filter(|step| matches!(step, Step::Refinement(..))).count(), refinements);
+}
+
+#[test]
+fn advance_to_next_placement_after_undo() {
+    let mut fresh = Solver::new(parse(GRID));
+    let hints: Vec<Hint> = std::iter::from_fn(|| fresh.advance_to_next_placement().ok()).collect();
+
+    let mut solver = Solver::new(parse(GRID));
+
+    for _ in 0..3 {
+        solver.advance_to_next_placement().expect("Progress");
+    }
+
+    while solver.undo() {}
+
+    let replayed: Vec<Hint> = std::iter::from_fn(|| solver.advance_to_next_placement().ok()).collect();
+
+    assert_eq!(hints, replayed);
+}
+
+#[test]
+fn verify() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let solution = solver.grid();
+
+    assert!(solver.verify(&solution).is_ok());
+
+    let refinements = solver.refinements().get_events();
+    let index = refinements.iter()
+        .position(|refinement| parse(GRID).get_digit(refinement.cell()).is_none())
+        .expect("Refinement of an empty cell");
+
+    let mut wrong = solution;
+    wrong.set_digit(refinements[index].cell(), Some(refinements[index].removed()));
+
+    let Err(error) = solver.verify(&wrong) else { panic!("Expected an unsound deduction") };
+
+    assert_eq!(&Step::Refinement(index, Box::new(refinements[index].clone())), error.step());
+}
+
+#[test]
+fn mistakes() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.assume_uniqueness().expect("Unique");
+
+    //  The solution of the first cell is 4, and 5 does not conflict with the givens.
+    let cell = CellIndex::new(0).expect("Valid CellIndex");
+    let (right, wrong) = (Digit::new(4).expect("Valid Digit"), Digit::new(5).expect("Valid Digit"));
+
+    assert!(matches!(solver.set_digit(cell, wrong), Err(MoveError::Mistake(_))));
+    assert!(matches!(solver.eliminate(cell, right), Err(MoveError::Mistake(_))));
+    assert!(!solver.can_undo());
+
+    solver.set_mistake_mode(MistakeMode::Lenient);
+
+    let mistake = solver.set_digit(cell, wrong).expect("Lenient").expect("Proven");
+
+    assert!(mistake.is_placement());
+    assert_eq!(1, mistake.proof().len());
+
+    assert!(solver.undo());
+    assert_eq!(None, solver.set_digit(cell, right).expect("Right"));
+}
+
+#[test]
+fn mistakes_emptying_cell() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.assume_uniqueness().expect("Unique");
+    solver.set_mistake_mode(MistakeMode::Lenient);
+    solver.advance_to_next_placement().expect("Progress");
+
+    //  Setting each candidate of a bivalue cell in one of its peers leaves it without candidates.
+    let possible_values = solver.possible_values();
+    let target = CellIndex::all().find(|cell| possible_values.of_cell(*cell).size() == 2).expect("Bivalue cell");
+
+    for digit in possible_values.of_cell(target) {
+        let (grid, possible_values) = (solver.grid(), solver.possible_values());
+        let peer = CellSet::peers(target)
+            .into_iter()
+            .filter(|peer| grid.get_digit(*peer).is_none() && grid.get_conflicting(*peer, digit).is_none())
+            .find(|peer| possible_values.of_cell(*peer).has(digit))
+            .expect("Peer");
+
+        solver.set_digit(peer, digit).expect("Lenient");
+    }
+
+    assert!(solver.solve().is_err());
+    assert!(solver.possible_values().of_cell(target).is_empty());
+}
+
+#[test]
+fn solve_on_another_thread() {
+    fn is_send_sync<T: Send + Sync>() {}
+
+    is_send_sync::<Solver>();
+
+    let mut solver = Solver::new(parse(GRID));
+    let refinements = solver.refinements();
+
+    let solver = std::thread::spawn(move || {
+        solver.solve().expect("Solvable");
+        solver
+    });
+
+    let solver = solver.join().expect("No panic");
+
+    assert_eq!(DIMENSION * DIMENSION, solver.grid().number_solved());
+    assert_eq!(solver.refinements().len(), refinements.len());
+}
+
+#[test]
+fn solve_with_guesses() {
+    const HARD: &str = "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1";
+
+    let mut config = AnalyzerConfig::only(&[Analysis::GroupExclusion, Analysis::GroupInclusion, Analysis::BowmansBingo]);
+
+    //  Too shallow guesses never fail.
+    config.set_max_guess_depth(3);
+
+    let mut solver = Solver::new(parse(HARD));
+    solver.configure(config.clone());
+
+    assert!(solver.solve().is_err());
+
+    config.set_max_guess_depth(DEFAULT_MAX_GUESS_DEPTH);
+
+    let mut solver = Solver::new(parse(HARD));
+    solver.configure(config);
+
+    solver.solve().expect("Solvable");
+
+    assert_eq!(DIMENSION * DIMENSION, solver.grid().number_solved());
+
+    let first = solver.first_guess().expect("Guessed");
+
+    let RefinementReason::BowmansBingo(guess) = solver.refinements().get_events()[first].reason().clone() else {
+        panic!("Not a guess");
+    };
+
+    assert!(guess.depth() <= DEFAULT_MAX_GUESS_DEPTH);
+}
+
+#[test]
+fn premises_form_dag() {
+    let mut solver = Solver::new(parse(GRID));
+
+    solver.solve().expect("Solvable");
+
+    let placements = solver.placements().get_events();
+    let refinements = solver.refinements().get_events();
+
+    //  A placement follows the removals of the other digits of its cell, hence precedes any later refinement.
+    for (index, placement) in placements.iter().enumerate() {
+        for premise in placement.premises() {
+            let Premise::Refinement(removal) = *premise else { panic!("Expected a refinement, got {premise:?}") };
+
+            assert_eq!(placement.cell(), refinements[removal].cell());
+            assert!(solver.dependents(*premise).contains(&Premise::Placement(index)));
+        }
+    }
+
+    for (index, refinement) in refinements.iter().enumerate() {
+        for premise in refinement.premises() {
+            let position = match *premise {
+                Premise::Placement(placement) => placements[placement].premises().iter().max().copied(),
+                Premise::Refinement(_) => Some(*premise),
+            };
+
+            assert!(position.is_none_or(|position| position < Premise::Refinement(index)), "{premise:?} of #{index}");
+            assert!(solver.dependents(*premise).contains(&Premise::Refinement(index)));
+        }
+    }
+
+    let placement = placements.iter().find(|placement| !placement.premises().is_empty()).expect("Deduced");
+
+    //  The premises are not part of the identity of a placement.
+    assert_eq!(Placement::new(placement.cell(), placement.digit()), *placement);
+}
+
+}
+
@@ -0,0 +1,57 @@
+//! Helpers shared by the tests of the various modules.
+
+use crate::model::{CellIndex, ColumnIndex, Digit, Grid, Group, RowIndex, SquareIndex};
+use crate::solver::PossibleValues;
+
+//  Parses a grid: 81 characters, left-to-right and top-to-bottom, any character other than 1 to 9 being unknown.
+pub(crate) fn parse(line: &str) -> Grid {
+    let mut grid = Grid::default();
+
+    for (index, byte) in line.bytes().enumerate() {
+        if let b'1'..=b'9' = byte {
+            let cell = CellIndex::new(index).expect("Valid CellIndex");
+            grid.set_digit(cell, Digit::new((byte - b'0') as usize).ok());
+        }
+    }
+
+    grid
+}
+
+//  Parses possible values: 81 whitespace-separated lists of possible digits, left-to-right and top-to-bottom.
+pub(crate) fn parse_candidates(text: &str) -> PossibleValues {
+    let mut possible_values = PossibleValues::all();
+    let mut count = 0;
+
+    for (index, token) in text.split_whitespace().enumerate() {
+        let cell = CellIndex::new(index).expect("Valid CellIndex");
+
+        for digit in 1..=9 {
+            if !token.contains(char::from(b'0' + digit as u8)) {
+                possible_values.remove_possibility(cell, Digit::new(digit).expect("Valid Digit"));
+            }
+        }
+
+        count += 1;
+    }
+
+    assert_eq!(81, count, "Expected 81 cells");
+
+    possible_values
+}
+
+//  Returns the cell at the specified row and column, both 1-based as in the usual r1c1 notation.
+pub(crate) fn cell(row: usize, column: usize) -> CellIndex {
+    CellIndex::new((row - 1) * 9 + column - 1).expect("Valid CellIndex")
+}
+
+//  Returns the digit.
+pub(crate) fn digit(digit: usize) -> Digit { Digit::new(digit).expect("Valid Digit") }
+
+//  Returns the row, 1-based.
+pub(crate) fn row(row: usize) -> Group { Group::Row(RowIndex::new(row - 1).expect("Valid RowIndex")) }
+
+//  Returns the column, 1-based.
+pub(crate) fn column(column: usize) -> Group { Group::Column(ColumnIndex::new(column - 1).expect("Valid ColumnIndex")) }
+
+//  Returns the square, 1-based, left-to-right and top-to-bottom.
+pub(crate) fn square(square: usize) -> Group { Group::Square(SquareIndex::new(square - 1).expect("Valid SquareIndex")) }